@@ -0,0 +1,138 @@
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::hash::Hash;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::{KmpMatchable, KmpPattern, KmpSearchable};
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Returns `true` if every haystack containing a match of `other` also contains a match
+    /// of `self`.
+    ///
+    /// The check is structural and conservative: it holds when `other`'s needle contains
+    /// `self`'s needle as a factor, compared element-wise with
+    /// [`KmpSearchable::is_match_guaranteed`]. A `false` result does not prove that some
+    /// haystack matches `other` but not `self`.
+    pub fn subsumes(&self, other: &KmpPattern<N>) -> bool
+    where
+        N: KmpSearchable,
+    {
+        let needle = self.needle;
+        let other = other.needle;
+
+        if needle.is_empty() {
+            return true;
+        }
+
+        if needle.len() > other.len() {
+            return false;
+        }
+
+        other.windows(needle.len()).any(|window| {
+            window
+                .iter()
+                .zip(needle)
+                .all(|(item, needle_item)| item.is_match_guaranteed(needle_item))
+        })
+    }
+
+    /// Smallest set of haystack positions such that every overlapping match contains at
+    /// least one of them, in ascending order.
+    ///
+    /// All matches have the same length, so greedily picking the last position of the
+    /// leftmost match not yet covered is optimal. Returns an empty set if there are no
+    /// matches, and also for the empty needle since its zero-length matches contain no
+    /// positions.
+    pub fn hitting_set<H>(&self, haystack: &[H]) -> Vec<usize>
+    where
+        N: KmpMatchable<H>,
+    {
+        let needle_len = self.needle.len();
+        let mut points = Vec::new();
+
+        if needle_len == 0 {
+            return points;
+        }
+
+        for pos in self.find_overlapping(haystack) {
+            if points.last().is_some_and(|&point| point >= pos) {
+                continue;
+            }
+
+            points.push(pos + needle_len - 1);
+        }
+
+        points
+    }
+
+    /// Start of the overlapping match whose preceding haystack item is the least frequent
+    /// in the whole haystack. Ties go to the leftmost match.
+    ///
+    /// A match at position 0 has no preceding item and is only returned if there is no
+    /// other match.
+    #[cfg(feature = "std")]
+    pub fn rarest_anchored_match<H>(&self, haystack: &[H]) -> Option<usize>
+    where
+        N: KmpMatchable<H>,
+        H: Eq + Hash,
+    {
+        let mut frequencies: HashMap<&H, usize> = HashMap::new();
+        for item in haystack {
+            *frequencies.entry(item).or_default() += 1;
+        }
+
+        let mut matches = self.find_overlapping(haystack).peekable();
+        let first = matches.next_if_eq(&0);
+
+        matches
+            .min_by_key(|&pos| (frequencies[&haystack[pos - 1]], pos))
+            .or(first)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::KmpPattern;
+
+    #[test]
+    fn subsumes() {
+        let ab = KmpPattern::new(b"ab");
+        let xaby = KmpPattern::new(b"xaby");
+        let ba = KmpPattern::new(b"ba");
+        let empty = KmpPattern::new(b"");
+
+        assert!(ab.subsumes(&xaby));
+        assert!(!xaby.subsumes(&ab));
+        assert!(ab.subsumes(&ab));
+        assert!(!ab.subsumes(&ba));
+        assert!(empty.subsumes(&ab));
+        assert!(empty.subsumes(&empty));
+        assert!(!ab.subsumes(&empty));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn rarest_anchored_match() {
+        let pattern = KmpPattern::new(b"ab");
+        assert_eq!(pattern.rarest_anchored_match(b"xabxabyabxab"), Some(7));
+        assert_eq!(pattern.rarest_anchored_match(b"xabxab"), Some(1));
+        assert_eq!(pattern.rarest_anchored_match(b"abab"), Some(2));
+        assert_eq!(pattern.rarest_anchored_match(b"abxx"), Some(0));
+        assert_eq!(pattern.rarest_anchored_match(b"xxxx"), None);
+    }
+
+    #[test]
+    fn hitting_set() {
+        let pattern = KmpPattern::new(b"aa");
+        assert_eq!(pattern.hitting_set(b"aaaaa"), [1, 3]);
+        assert_eq!(pattern.hitting_set(b"aaxaa"), [1, 4]);
+        assert!(pattern.hitting_set(b"xyz").is_empty());
+
+        let pattern = KmpPattern::new(b"aba");
+        assert_eq!(pattern.hitting_set(b"abababa"), [2, 6]);
+
+        let empty = KmpPattern::<u8>::new(b"");
+        assert!(empty.hitting_set(b"abc").is_empty());
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{item_matches, KmpMatchable, KmpPattern};
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Returns `true` if a match starts exactly at `pos`.
+    ///
+    /// This compares the needle element-wise and does not use the failure table. The empty
+    /// needle matches at every position up to and including `haystack.len()`.
+    pub fn matches_at<H>(&self, haystack: &[H], pos: usize) -> bool
+    where
+        N: KmpMatchable<H>,
+    {
+        match haystack.get(pos..) {
+            Some(tail) if tail.len() >= self.needle.len() => self
+                .needle
+                .iter()
+                .zip(tail)
+                .all(|(needle_item, item)| item_matches(needle_item, item)),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the haystack begins with a match.
+    pub fn starts_with<H>(&self, haystack: &[H]) -> bool
+    where
+        N: KmpMatchable<H>,
+    {
+        self.matches_at(haystack, 0)
+    }
+
+    /// Returns `true` if the haystack ends with a match.
+    pub fn ends_with<H>(&self, haystack: &[H]) -> bool
+    where
+        N: KmpMatchable<H>,
+    {
+        haystack
+            .len()
+            .checked_sub(self.needle.len())
+            .is_some_and(|pos| self.matches_at(haystack, pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::KmpPattern;
+
+    #[test]
+    fn matches_at() {
+        let pattern = KmpPattern::new(b"ab");
+        assert!(pattern.matches_at(b"xabab", 1));
+        assert!(pattern.matches_at(b"xabab", 3));
+        assert!(!pattern.matches_at(b"xabab", 2));
+        assert!(!pattern.matches_at(b"xabab", 4));
+        assert!(!pattern.matches_at(b"xabab", 6));
+    }
+
+    #[test]
+    fn starts_with() {
+        let pattern = KmpPattern::new(b"ab");
+        assert!(pattern.starts_with(b"abc"));
+        assert!(!pattern.starts_with(b"cab"));
+        assert!(!pattern.starts_with(b"a"));
+    }
+
+    #[test]
+    fn ends_with() {
+        let pattern = KmpPattern::new(b"ab");
+        assert!(pattern.ends_with(b"cab"));
+        assert!(pattern.ends_with(b"ab"));
+        assert!(!pattern.ends_with(b"abc"));
+        assert!(!pattern.ends_with(b"b"));
+    }
+
+    #[test]
+    fn empty_needle() {
+        let pattern = KmpPattern::<u8>::new(b"");
+        assert!(pattern.matches_at(b"ab", 0));
+        assert!(pattern.matches_at(b"ab", 2));
+        assert!(!pattern.matches_at(b"ab", 3));
+        assert!(pattern.starts_with(b""));
+        assert!(pattern.ends_with(b""));
+        assert!(pattern.ends_with(b"ab"));
+    }
+}
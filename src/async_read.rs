@@ -0,0 +1,254 @@
+use core::future::poll_fn;
+use core::pin::Pin;
+use core::task::{ready, Context, Poll};
+use std::io;
+
+#[cfg(feature = "futures")]
+use futures_core::Stream;
+use tokio::io::{AsyncRead, ReadBuf};
+
+use crate::stream::READ_CHUNK;
+use crate::{KmpIncremental, KmpMatchable, KmpPattern, KmpTableItem};
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Non-overlapping search over the bytes of an asynchronous `reader`.
+    pub fn find_async<R>(&'a self, reader: R) -> KmpAsyncSearch<'a, N, R, false>
+    where
+        N: KmpMatchable<u8>,
+        R: AsyncRead + Unpin,
+    {
+        KmpAsyncSearch::new(self.needle, &self.lsp, reader)
+    }
+
+    /// Overlapping search over the bytes of an asynchronous `reader`.
+    pub fn find_async_overlapping<R>(&'a self, reader: R) -> KmpAsyncSearch<'a, N, R, true>
+    where
+        N: KmpMatchable<u8>,
+        R: AsyncRead + Unpin,
+    {
+        KmpAsyncSearch::new(self.needle, &self.lsp, reader)
+    }
+}
+
+/// Search over an [`AsyncRead`] source, yielding absolute byte offsets of matches.
+///
+/// The asynchronous counterpart of [`KmpStreamSearch`](crate::KmpStreamSearch): the state
+/// of the automaton and the bytes of the current partial match are kept across reads, so
+/// matches spanning read boundaries are found with bounded memory.
+///
+/// With the `futures` feature, it is also a [`Stream`] of the offsets returned by
+/// [`KmpAsyncSearch::next_match`].
+pub struct KmpAsyncSearch<'a, N, R, const OVERLAPPING: bool> {
+    state: KmpIncremental<'a, N, u8, OVERLAPPING>,
+    reader: R,
+    eof: bool,
+    finished: bool,
+}
+
+impl<'a, N, R, const OVERLAPPING: bool> KmpAsyncSearch<'a, N, R, OVERLAPPING> {
+    pub fn new(needle: &'a [N], lsp: &'a [KmpTableItem], reader: R) -> Self {
+        Self {
+            state: KmpIncremental::new(needle, lsp),
+            reader,
+            eof: false,
+            finished: false,
+        }
+    }
+
+    /// Automaton state as `(needle_pos, haystack_pos)`, see [`KmpIncremental::state`].
+    pub fn state(&self) -> (usize, u64) {
+        self.state.state()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<'a, N, R, const OVERLAPPING: bool> KmpAsyncSearch<'a, N, R, OVERLAPPING>
+where
+    N: KmpMatchable<u8>,
+    R: AsyncRead + Unpin,
+{
+    /// Offset of the next match, reading more input as needed. Returns `None` at the end of
+    /// the input, and keeps returning `None` afterwards.
+    pub async fn next_match(&mut self) -> Option<io::Result<u64>> {
+        poll_fn(|cx| self.poll_next_match(cx)).await
+    }
+
+    fn poll_next_match(&mut self, cx: &mut Context<'_>) -> Poll<Option<io::Result<u64>>> {
+        loop {
+            if let Some(pos) = self.state.next_match() {
+                return Poll::Ready(Some(Ok(pos)));
+            }
+
+            if self.eof {
+                if self.finished {
+                    return Poll::Ready(None);
+                }
+
+                // The empty needle also matches once at the very end of the stream.
+                self.finished = true;
+                if self.state.needle.is_empty() {
+                    return Poll::Ready(Some(Ok(self.state.stream_len())));
+                }
+
+                return Poll::Ready(None);
+            }
+
+            if let Err(err) = ready!(self.poll_refill(cx)) {
+                return Poll::Ready(Some(Err(err)));
+            }
+        }
+    }
+
+    fn poll_refill(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.state.compact();
+
+        let buffer = &mut self.state.buffer;
+        let filled = buffer.len();
+        buffer.resize(filled + READ_CHUNK, 0);
+
+        let mut read_buf = ReadBuf::new(&mut buffer[filled..]);
+        let result = Pin::new(&mut self.reader).poll_read(cx, &mut read_buf);
+        let read = read_buf.filled().len();
+        buffer.truncate(filled + read);
+
+        ready!(result)?;
+        if read == 0 {
+            self.eof = true;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<'a, N, R, const OVERLAPPING: bool> Stream for KmpAsyncSearch<'a, N, R, OVERLAPPING>
+where
+    N: KmpMatchable<u8>,
+    R: AsyncRead + Unpin,
+{
+    type Item = io::Result<u64>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_next_match(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::pin::Pin;
+    use core::task::{Context, Poll};
+    use std::io;
+
+    use tokio::io::{AsyncRead, ReadBuf};
+
+    use crate::KmpPattern;
+
+    /// Reads one byte at a time, returning `Pending` before every byte.
+    struct OneByte<'a> {
+        data: &'a [u8],
+        ready: bool,
+    }
+
+    impl AsyncRead for OneByte<'_> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            if !self.ready {
+                self.ready = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+
+            self.ready = false;
+            if let Some((&first, rest)) = self.data.split_first() {
+                buf.put_slice(&[first]);
+                self.data = rest;
+            }
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn one_byte(data: &[u8]) -> OneByte<'_> {
+        OneByte { data, ready: false }
+    }
+
+    #[tokio::test]
+    async fn one_byte_reads() {
+        const TEST_CASES: &[(&[u8], &[u8])] = &[
+            (b"abc", b"abcabc"),
+            (b"aa", b"aaaaa"),
+            (b"abab", b"xabababababx"),
+            (b"xyz", b"abc"),
+            (b"", b"abc"),
+            (b"abc", b""),
+        ];
+
+        for (needle, haystack) in TEST_CASES {
+            let pattern = KmpPattern::new(needle);
+
+            let mut search = pattern.find_async(one_byte(haystack));
+            let mut found = vec![];
+            while let Some(pos) = search.next_match().await {
+                found.push(pos.unwrap() as usize);
+            }
+            assert_eq!(found, pattern.find(haystack).collect::<Vec<_>>());
+            assert!(search.next_match().await.is_none());
+
+            let mut search = pattern.find_async_overlapping(one_byte(haystack));
+            let mut found = vec![];
+            while let Some(pos) = search.next_match().await {
+                found.push(pos.unwrap() as usize);
+            }
+            assert_eq!(
+                found,
+                pattern.find_overlapping(haystack).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn large_reads() {
+        let mut haystack = Vec::new();
+        for i in 0..10_000u32 {
+            haystack.extend_from_slice(if i % 7 == 0 { b"needle" } else { b"hay" });
+        }
+
+        let pattern = KmpPattern::new(b"needle");
+        let mut search = pattern.find_async(haystack.as_slice());
+        let mut found = vec![];
+        while let Some(pos) = search.next_match().await {
+            found.push(pos.unwrap() as usize);
+        }
+        assert_eq!(found, pattern.find(&haystack).collect::<Vec<_>>());
+        assert_eq!(search.state(), (0, haystack.len() as u64));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "futures")]
+    async fn stream() {
+        use futures::{StreamExt, TryStreamExt};
+
+        let haystack = b"xabcabcxxabcx";
+        let pattern = KmpPattern::new(b"abc");
+
+        let found: Vec<u64> = pattern
+            .find_async(one_byte(haystack))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(found, [1, 4, 9]);
+
+        let first: Vec<_> = pattern
+            .find_async_overlapping(one_byte(haystack))
+            .take(2)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(first, [1, 4]);
+    }
+}
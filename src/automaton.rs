@@ -0,0 +1,76 @@
+use alloc::vec::Vec;
+
+use crate::{KmpIndex, KmpPattern};
+
+/// State of the search automaton after matching the needle prefix ending at `index`.
+///
+/// This is the table item the search falls back through when the item following that
+/// prefix does not match, or after a complete match for the last node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AutomatonNode {
+    /// Index of the needle item ending the matched prefix.
+    pub index: usize,
+    /// Needle position the search resumes from, the failure link target.
+    pub needle: usize,
+    /// Number of haystack items read again after falling back, `0` unless the border is
+    /// only possibly matched.
+    pub haystack: usize,
+}
+
+impl<'a, N, I: KmpIndex> KmpPattern<'a, N, I> {
+    /// One node per needle item, read from the table used by the search.
+    pub fn automaton(&self) -> Vec<AutomatonNode> {
+        self.table()
+            .iter()
+            .enumerate()
+            .map(|(index, item)| AutomatonNode {
+                index,
+                needle: item.needle(),
+                haystack: item.haystack(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{AutomatonNode, KmpPattern, Wildcard};
+
+    #[test]
+    fn automaton() {
+        let pattern = KmpPattern::new(b"abacabab");
+        let nodes = pattern.automaton();
+
+        let failure: Vec<_> = nodes.iter().map(|node| node.needle).collect();
+        assert_eq!(failure, [0, 0, 1, 0, 1, 2, 3, 2]);
+        assert!(nodes.iter().all(|node| node.haystack == 0));
+        assert!(nodes.iter().enumerate().all(|(i, node)| node.index == i));
+
+        assert!(KmpPattern::<u8>::new(b"").automaton().is_empty());
+    }
+
+    #[test]
+    fn wildcard_corrections() {
+        let needle = [
+            Wildcard::Exact(b'a'),
+            Wildcard::Any,
+            Wildcard::Exact(b'a'),
+            Wildcard::Exact(b'b'),
+        ];
+        let pattern = KmpPattern::new(&needle);
+
+        // `?` and `a?a` only possibly match their borders `a` and `a?`, so their items are
+        // read again, while `ab` is guaranteed to match `a?`.
+        let node = |index, needle, haystack| AutomatonNode {
+            index,
+            needle,
+            haystack,
+        };
+        assert_eq!(
+            pattern.automaton(),
+            [node(0, 0, 0), node(1, 1, 1), node(2, 2, 2), node(3, 2, 0)]
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use alloc::vec::Vec;
+
+use crate::{KmpError, KmpOwnedPattern, PatternElement};
+
+/// Builds a needle of [`PatternElement`]s item by item, compiling it into a
+/// [`KmpOwnedPattern`].
+#[derive(Debug, Clone)]
+pub struct KmpNeedleBuilder<T> {
+    elements: Vec<PatternElement<T>>,
+}
+
+impl<T> Default for KmpNeedleBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> KmpNeedleBuilder<T> {
+    pub fn new() -> Self {
+        Self {
+            elements: Vec::new(),
+        }
+    }
+
+    /// Appends an element matching `value` only.
+    pub fn exact(mut self, value: T) -> Self {
+        self.elements.push(PatternElement::Exact(value));
+        self
+    }
+
+    /// Appends an element matching any item.
+    pub fn any(mut self) -> Self {
+        self.elements.push(PatternElement::Any);
+        self
+    }
+
+    /// Appends an element matching any of `values`.
+    pub fn one_of<I: IntoIterator<Item = T>>(mut self, values: I) -> Self {
+        self.elements
+            .push(PatternElement::OneOf(values.into_iter().collect()));
+        self
+    }
+
+    /// Appends an element matching any item except `value`.
+    pub fn not(mut self, value: T) -> Self {
+        self.elements.push(PatternElement::Not(value));
+        self
+    }
+
+    /// Compiles the needle, checking it as [`KmpPattern::try_new`] does. An empty
+    /// [`KmpNeedleBuilder::one_of`] set is rejected, as it can never match.
+    ///
+    /// [`KmpPattern::try_new`]: crate::KmpPattern::try_new
+    pub fn build(self) -> Result<KmpOwnedPattern<PatternElement<T>>, KmpError>
+    where
+        T: PartialEq,
+    {
+        KmpOwnedPattern::try_new(self.elements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{KmpError, KmpNeedleBuilder, PatternElement};
+
+    #[test]
+    fn build() {
+        let pattern = KmpNeedleBuilder::new()
+            .exact(b'a')
+            .any()
+            .exact(b'c')
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            pattern.needle(),
+            [
+                PatternElement::Exact(b'a'),
+                PatternElement::Any,
+                PatternElement::Exact(b'c'),
+            ]
+        );
+        assert_eq!(pattern.find(b"aXc").collect::<Vec<_>>(), [0]);
+        assert!(pattern.find(b"ac").next().is_none());
+    }
+
+    #[test]
+    fn one_of_and_not() {
+        let pattern = KmpNeedleBuilder::new()
+            .one_of(*b"ab")
+            .not(b'x')
+            .exact(b'a')
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            pattern.find_overlapping(b"aaabxaxbya").collect::<Vec<_>>(),
+            [0, 7]
+        );
+    }
+
+    #[test]
+    fn empty_one_of() {
+        let error = KmpNeedleBuilder::new()
+            .exact(b'a')
+            .one_of(None)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(error, KmpError::UnmatchableItem { index: 1 });
+    }
+}
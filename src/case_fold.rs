@@ -0,0 +1,173 @@
+use crate::{KmpMatchable, KmpOwnedPattern, KmpSearchable};
+
+/// Needle element matching a `char` regardless of case, under Unicode simple case folding.
+///
+/// Only one-to-one mappings are applied. Full folding into several characters is not
+/// supported, so `'ß'` does not match `"ss"`, and `'İ'`, which has no simple folding, only
+/// matches itself. The Turkic mappings of dotted and dotless `i` are not applied either.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CaseFold(pub char);
+
+impl CaseFold {
+    fn folded(&self) -> char {
+        fold(self.0)
+    }
+}
+
+impl KmpOwnedPattern<CaseFold> {
+    /// Pattern matching the chars of `needle` regardless of case, under the simple folding
+    /// of [`CaseFold`]. Search `&str` haystacks with [`KmpPattern::find_str`] on
+    /// [`KmpOwnedPattern::to_borrowed`].
+    ///
+    /// [`KmpPattern::find_str`]: crate::KmpPattern::find_str
+    pub fn new_caseless_str(needle: &str) -> Self {
+        Self::new(needle.chars().map(CaseFold).collect())
+    }
+}
+
+/// Representative of the simple case folding class of `c`.
+///
+/// The single-`char` lowercase mapping agrees with simple folding up to the characters
+/// listed here, whose lowercase is themselves but which fold onto another letter.
+fn fold(c: char) -> char {
+    match c {
+        '\u{b5}' => '\u{3bc}',
+        '\u{17f}' => 's',
+        '\u{345}' | '\u{1fbe}' => '\u{3b9}',
+        '\u{3c2}' => '\u{3c3}',
+        '\u{3d0}' => '\u{3b2}',
+        '\u{3d1}' => '\u{3b8}',
+        '\u{3d5}' => '\u{3c6}',
+        '\u{3d6}' => '\u{3c0}',
+        '\u{3f0}' => '\u{3ba}',
+        '\u{3f1}' => '\u{3c1}',
+        '\u{3f5}' => '\u{3b5}',
+        '\u{1c80}' => '\u{432}',
+        '\u{1c81}' => '\u{434}',
+        '\u{1c82}' => '\u{43e}',
+        '\u{1c83}' => '\u{441}',
+        '\u{1c84}' | '\u{1c85}' => '\u{442}',
+        '\u{1c86}' => '\u{44a}',
+        '\u{1c87}' => '\u{463}',
+        '\u{1c88}' => '\u{a64b}',
+        '\u{1e9b}' => '\u{1e61}',
+        _ => {
+            let mut lower = c.to_lowercase();
+            match (lower.next(), lower.next()) {
+                (Some(folded), None) => folded,
+                _ => c,
+            }
+        }
+    }
+}
+
+impl KmpSearchable for CaseFold {
+    fn is_match_possible(&self, other: &Self) -> bool {
+        self.folded() == other.folded()
+    }
+
+    fn is_match_guaranteed(&self, other: &Self) -> bool {
+        self.folded() == other.folded()
+    }
+}
+
+impl KmpMatchable<CaseFold> for CaseFold {
+    fn match_haystack(&self, other: &CaseFold) -> bool {
+        self.folded() == other.folded()
+    }
+}
+
+impl KmpMatchable<char> for CaseFold {
+    fn match_haystack(&self, other: &char) -> bool {
+        self.folded() == fold(*other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{CaseFold, KmpOwnedPattern, KmpPattern};
+
+    #[test]
+    fn case_fold() {
+        let needle = ['Ä', 'b', 'Σ'].map(CaseFold);
+        let pattern = KmpPattern::new(&needle);
+
+        let haystack: Vec<_> = "xäBσ ÄBΣ".chars().collect();
+        assert_eq!(pattern.find(&haystack).collect::<Vec<_>>(), [1, 5]);
+    }
+
+    #[test]
+    fn new_caseless_str() {
+        let owned = KmpOwnedPattern::new_caseless_str("Straße");
+        let pattern = owned.to_borrowed();
+
+        assert_eq!(
+            pattern
+                .find_str("die STRAßE, die straße")
+                .collect::<Vec<_>>(),
+            [4, 17]
+        );
+        assert!(pattern.find_str("STRASSE").next().is_none());
+    }
+
+    #[test]
+    fn case_fold_beyond_lowercase() {
+        let pairs = [
+            ('σ', 'ς'),
+            ('Σ', 'ς'),
+            ('s', 'ſ'),
+            ('S', 'ſ'),
+            ('β', 'ϐ'),
+            ('Θ', 'ϑ'),
+            ('μ', 'µ'),
+            ('Ι', '\u{1fbe}'),
+            ('В', '\u{1c80}'),
+            ('Ṡ', 'ẛ'),
+            ('Ꭰ', 'ꭰ'),
+        ];
+
+        for (a, b) in pairs {
+            let needle = [CaseFold(a), CaseFold(b)];
+            let pattern = KmpPattern::new(&needle);
+            assert_eq!(pattern.find(&[b, a]).collect::<Vec<_>>(), [0], "{a} {b}");
+        }
+
+        let owned = KmpOwnedPattern::new_caseless_str("ΟΔΟΣ");
+        assert_eq!(
+            owned.to_borrowed().find_str("οδος").collect::<Vec<_>>(),
+            [0]
+        );
+    }
+
+    #[test]
+    fn case_fold_is_one_to_one() {
+        let pattern = KmpPattern::new(&[CaseFold('İ')]);
+        let lowercased: Vec<_> = "İ".to_lowercase().chars().collect();
+
+        assert_eq!(lowercased, ['i', '\u{307}']);
+        assert!(pattern.find(&lowercased).next().is_none());
+        assert!(pattern.find(&['i']).next().is_none());
+        assert_eq!(pattern.find(&['İ']).collect::<Vec<_>>(), [0]);
+
+        let pattern = KmpPattern::new(&[CaseFold('ß')]);
+        assert!(pattern.find(&['s', 's']).next().is_none());
+        assert_eq!(pattern.find(&['ẞ']).collect::<Vec<_>>(), [0]);
+    }
+
+    #[test]
+    fn case_fold_table() {
+        let needle = ['A', 'a', 'Ö', 'A', 'a'].map(CaseFold);
+        let pattern = KmpPattern::new(&needle);
+        let lowercase = KmpPattern::new(&['a', 'a', 'ö', 'a', 'a']);
+
+        assert_eq!(pattern.table(), lowercase.table());
+        assert_eq!(
+            pattern
+                .find(&['a', 'A', 'a', 'ö', 'A', 'A'])
+                .collect::<Vec<_>>(),
+            [1]
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::mpsc::{SendError, Sender};
+
+use crate::{KmpMatchable, KmpPattern};
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Runs a non-overlapping search, sending each match position to `tx` as it is found.
+    ///
+    /// The scan stops early and returns the error if the receiver has been dropped.
+    pub fn find_into_sender<H>(
+        &self,
+        haystack: &[H],
+        tx: Sender<usize>,
+    ) -> Result<(), SendError<usize>>
+    where
+        N: KmpMatchable<H>,
+    {
+        for pos in self.find(haystack) {
+            tx.send(pos)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+    use std::thread;
+
+    use crate::KmpPattern;
+
+    #[test]
+    fn find_into_sender() {
+        let (tx, rx) = channel();
+        let consumer = thread::spawn(move || rx.iter().collect::<Vec<_>>());
+
+        let pattern = KmpPattern::new(b"ab");
+        pattern.find_into_sender(b"abxabyab", tx).unwrap();
+
+        assert_eq!(consumer.join().unwrap(), [0, 3, 6]);
+    }
+
+    #[test]
+    fn find_into_sender_receiver_dropped() {
+        let (tx, rx) = channel();
+        drop(rx);
+
+        let pattern = KmpPattern::new(b"ab");
+        let err = pattern.find_into_sender(b"xxabab", tx).unwrap_err();
+        assert_eq!(err.0, 2);
+    }
+}
@@ -0,0 +1,204 @@
+use alloc::collections::VecDeque;
+use core::iter::FusedIterator;
+use core::str::CharIndices;
+
+use crate::{KmpIncremental, KmpMatchable, KmpPattern, KmpSearch, KmpTableItem};
+
+const DECODE_CHUNK: usize = 1024;
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Non-overlapping search over the chars of `haystack`, yielding byte offsets of
+    /// matches so that they can be used to slice `haystack`.
+    pub fn find_str(&'a self, haystack: &'a str) -> KmpStrSearch<'a, N, false>
+    where
+        N: KmpMatchable<char>,
+    {
+        KmpStrSearch::new(self.needle, &self.lsp, haystack)
+    }
+
+    /// Overlapping search over the chars of `haystack`, yielding byte offsets of matches.
+    pub fn find_str_overlapping(&'a self, haystack: &'a str) -> KmpStrSearch<'a, N, true>
+    where
+        N: KmpMatchable<char>,
+    {
+        KmpStrSearch::new(self.needle, &self.lsp, haystack)
+    }
+}
+
+impl<'a> KmpPattern<'a, u8> {
+    /// Pattern matching the UTF-8 bytes of `needle`.
+    pub fn from_str_bytes(needle: &'a str) -> Self {
+        Self::new(needle.as_bytes())
+    }
+
+    /// Non-overlapping search over the bytes of `haystack`, yielding byte offsets.
+    ///
+    /// Unlike [`KmpPattern::find_str`], the haystack is not decoded. Since UTF-8 is
+    /// self-synchronizing, a pattern built by [`KmpPattern::from_str_bytes`] from a non-empty
+    /// `str` only matches at char boundaries.
+    pub fn find_str_bytes(&'a self, haystack: &'a str) -> KmpSearch<'a, u8, u8, false> {
+        self.find(haystack.as_bytes())
+    }
+
+    /// Overlapping counterpart of [`KmpPattern::find_str_bytes`].
+    pub fn find_str_bytes_overlapping(&'a self, haystack: &'a str) -> KmpSearch<'a, u8, u8, true> {
+        self.find_overlapping(haystack.as_bytes())
+    }
+}
+
+/// Search over a `&str` that decodes chars lazily and yields byte offsets of matches.
+///
+/// Only the chars of the current partial match are retained between decoded chunks, along
+/// with their byte offsets.
+pub struct KmpStrSearch<'a, N, const OVERLAPPING: bool> {
+    state: KmpIncremental<'a, N, char, OVERLAPPING>,
+    chars: CharIndices<'a>,
+    // Byte offset of each char in `state.buffer`.
+    offsets: VecDeque<usize>,
+    haystack_len: usize,
+    finished: bool,
+}
+
+impl<'a, N, const OVERLAPPING: bool> KmpStrSearch<'a, N, OVERLAPPING> {
+    pub fn new(needle: &'a [N], lsp: &'a [KmpTableItem], haystack: &'a str) -> Self {
+        Self {
+            state: KmpIncremental::new(needle, lsp),
+            chars: haystack.char_indices(),
+            offsets: VecDeque::new(),
+            haystack_len: haystack.len(),
+            finished: false,
+        }
+    }
+
+    /// Decodes the next chunk of chars, returning `false` at the end of the haystack.
+    fn refill(&mut self) -> bool {
+        self.state.compact();
+        let dropped = self.offsets.len() - self.state.buffer.len();
+        self.offsets.drain(..dropped);
+
+        let filled = self.state.buffer.len();
+        for (offset, c) in self.chars.by_ref().take(DECODE_CHUNK) {
+            self.state.buffer.push(c);
+            self.offsets.push_back(offset);
+        }
+
+        self.state.buffer.len() != filled
+    }
+}
+
+impl<'a, N, const OVERLAPPING: bool> Iterator for KmpStrSearch<'a, N, OVERLAPPING>
+where
+    N: KmpMatchable<char>,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pos) = self.state.next_match() {
+                return Some(self.offsets[(pos - self.state.offset) as usize]);
+            }
+
+            if !self.refill() {
+                if self.finished {
+                    return None;
+                }
+
+                // The empty needle also matches once at the very end of the haystack.
+                self.finished = true;
+                if self.state.needle.is_empty() {
+                    return Some(self.haystack_len);
+                }
+
+                return None;
+            }
+        }
+    }
+}
+
+impl<'a, N, const OVERLAPPING: bool> FusedIterator for KmpStrSearch<'a, N, OVERLAPPING> where
+    N: KmpMatchable<char>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CharClass, KmpPattern};
+
+    #[test]
+    fn str_bytes() {
+        let pattern = KmpPattern::from_str_bytes("ab");
+        assert_eq!(pattern.find_str_bytes("xabab").collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(pattern.find(b"xabab").collect::<Vec<_>>(), [1, 3]);
+
+        let pattern = KmpPattern::from_str_bytes("éé");
+        let haystack = "xéééy";
+        assert_eq!(pattern.find_str_bytes(haystack).collect::<Vec<_>>(), [1]);
+        assert_eq!(
+            pattern
+                .find_str_bytes_overlapping(haystack)
+                .collect::<Vec<_>>(),
+            [1, 3]
+        );
+        assert_eq!(&haystack[3..7], "éé");
+    }
+
+    #[test]
+    fn byte_offsets() {
+        let pattern = KmpPattern::new(&['é', 'x']);
+        let haystack = "aéxb";
+
+        let found: Vec<_> = pattern.find_str(haystack).collect();
+        assert_eq!(found, [1]);
+        assert_eq!(&haystack[found[0]..], "éxb");
+    }
+
+    #[test]
+    fn multi_byte() {
+        let pattern = KmpPattern::new(&['日', '本']);
+        let haystack = "日本語の日本";
+
+        let found: Vec<_> = pattern.find_str(haystack).collect();
+        assert_eq!(found, [0, 12]);
+
+        let pattern = KmpPattern::new(&['ö', 'ö']);
+        assert_eq!(pattern.find_str("öööö").collect::<Vec<_>>(), [0, 4]);
+        assert_eq!(
+            pattern.find_str_overlapping("öööö").collect::<Vec<_>>(),
+            [0, 2, 4]
+        );
+    }
+
+    #[test]
+    fn matches_char_search() {
+        let haystack = "ab€ab€€abab€".repeat(300);
+        let chars: Vec<char> = haystack.chars().collect();
+        let byte_offset = |pos: usize| haystack.char_indices().nth(pos).unwrap().0;
+
+        for needle in [&['a', 'b'][..], &['€', '€'], &['b', '€', 'a']] {
+            let pattern = KmpPattern::new(needle);
+
+            let expected: Vec<_> = pattern.find(&chars).map(byte_offset).collect();
+            assert_eq!(pattern.find_str(&haystack).collect::<Vec<_>>(), expected);
+
+            let expected: Vec<_> = pattern.find_overlapping(&chars).map(byte_offset).collect();
+            assert_eq!(
+                pattern.find_str_overlapping(&haystack).collect::<Vec<_>>(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn char_class() {
+        let needle = [CharClass::new(['à'..='ÿ']), CharClass::single('x')];
+        let pattern = KmpPattern::new(&needle);
+        assert_eq!(pattern.find_str("axéxüx").collect::<Vec<_>>(), [2, 5]);
+    }
+
+    #[test]
+    fn empty_needle() {
+        let pattern = KmpPattern::<char>::new(&[]);
+        assert_eq!(pattern.find_str("aé").collect::<Vec<_>>(), [0, 1, 3]);
+        assert_eq!(pattern.find_str("").collect::<Vec<_>>(), [0]);
+    }
+}
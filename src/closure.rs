@@ -0,0 +1,149 @@
+use core::iter::FusedIterator;
+
+use crate::{kmp_table_by, KmpOwnedTable, KmpSearch, KmpTable};
+
+/// A compiled pattern whose items are compared by closures instead of the
+/// [`KmpSearchable`] and [`KmpMatchable`] traits.
+///
+/// `relate(item, other)` is used to build the table. It returns `None` if no haystack item
+/// can match both needle items, and otherwise whether every haystack item matching `item`
+/// also matches `other`. `matcher` compares a needle item with a haystack item while
+/// searching. Both must be consistent with each other and give the same answer every time
+/// they are called with the same items, or the table does not describe the needle and
+/// matches are missed or misreported.
+///
+/// [`KmpSearchable`]: crate::KmpSearchable
+/// [`KmpMatchable`]: crate::KmpMatchable
+pub struct KmpClosurePattern<'a, N, M> {
+    needle: &'a [N],
+    lsp: KmpOwnedTable,
+    matcher: M,
+}
+
+impl<'a, N, M> KmpClosurePattern<'a, N, M> {
+    pub fn new<R>(needle: &'a [N], relate: R, matcher: M) -> Self
+    where
+        R: Fn(&N, &N) -> Option<bool>,
+    {
+        Self {
+            needle,
+            lsp: kmp_table_by(needle, relate),
+            matcher,
+        }
+    }
+
+    pub fn table(&self) -> KmpTable<'_> {
+        &self.lsp
+    }
+
+    pub fn find<'s, H>(&'s mut self, haystack: &'s [H]) -> KmpClosureSearch<'s, N, H, M, false>
+    where
+        M: FnMut(&N, &H) -> bool,
+    {
+        KmpClosureSearch {
+            search: KmpSearch::new(self.needle, &self.lsp, haystack),
+            matcher: &mut self.matcher,
+        }
+    }
+
+    pub fn find_overlapping<'s, H>(
+        &'s mut self,
+        haystack: &'s [H],
+    ) -> KmpClosureSearch<'s, N, H, M, true>
+    where
+        M: FnMut(&N, &H) -> bool,
+    {
+        KmpClosureSearch {
+            search: KmpSearch::new(self.needle, &self.lsp, haystack),
+            matcher: &mut self.matcher,
+        }
+    }
+}
+
+/// Iterator over the matches of a [`KmpClosurePattern`].
+pub struct KmpClosureSearch<'s, N, H, M, const OVERLAPPING: bool> {
+    search: KmpSearch<'s, N, H, OVERLAPPING>,
+    matcher: &'s mut M,
+}
+
+impl<'s, N, H, M, const OVERLAPPING: bool> Iterator for KmpClosureSearch<'s, N, H, M, OVERLAPPING>
+where
+    M: FnMut(&N, &H) -> bool,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.search.next_by(&mut *self.matcher)
+    }
+}
+
+impl<'s, N, H, M, const OVERLAPPING: bool> FusedIterator
+    for KmpClosureSearch<'s, N, H, M, OVERLAPPING>
+where
+    M: FnMut(&N, &H) -> bool,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ops::RangeInclusive;
+
+    use crate::KmpClosurePattern;
+
+    fn relate(item: &RangeInclusive<u32>, other: &RangeInclusive<u32>) -> Option<bool> {
+        let overlaps = item.start() <= other.end() && other.start() <= item.end();
+        let contained = other.start() <= item.start() && item.end() <= other.end();
+
+        overlaps.then_some(contained)
+    }
+
+    fn matcher(item: &RangeInclusive<u32>, value: &u32) -> bool {
+        item.contains(value)
+    }
+
+    #[test]
+    fn integer_ranges() {
+        let needle = [1..=3, 5..=5];
+        let mut pattern = KmpClosurePattern::new(&needle, relate, matcher);
+
+        assert_eq!(
+            pattern.find(&[0, 2, 5, 3, 5, 4, 5]).collect::<Vec<_>>(),
+            [1, 3]
+        );
+    }
+
+    #[test]
+    fn partial_match_shift() {
+        // `1 2` fails on the `9` needle item, and the table shifts to retry the `2` as the
+        // start of a match.
+        let needle = [0..=2, 2..=4, 9..=9];
+        let mut pattern = KmpClosurePattern::new(&needle, relate, matcher);
+
+        assert_eq!(pattern.table()[1].needle, 1);
+        assert_eq!(pattern.find(&[1, 2, 3, 9]).collect::<Vec<_>>(), [1]);
+        assert_eq!(
+            pattern
+                .find_overlapping(&[2, 2, 2, 9, 1, 4, 9])
+                .collect::<Vec<_>>(),
+            [1, 4]
+        );
+    }
+
+    #[test]
+    fn stateful_matcher() {
+        let needle = [1..=1, 2..=2];
+        let mut comparisons = 0;
+        let mut pattern = KmpClosurePattern::new(
+            &needle,
+            relate,
+            |item: &RangeInclusive<u32>, value: &u32| {
+                comparisons += 1;
+                matcher(item, value)
+            },
+        );
+
+        assert_eq!(pattern.find(&[1, 2, 1, 2]).count(), 2);
+        drop(pattern);
+        assert_eq!(comparisons, 4);
+    }
+}
@@ -0,0 +1,305 @@
+use crate::{item_matches, KmpMatchable, KmpPattern, KmpSearch};
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Returns `true` if the pattern occurs in `haystack`, stopping at the first match.
+    pub fn contains<H>(&self, haystack: &[H]) -> bool
+    where
+        N: KmpMatchable<H>,
+    {
+        self.is_match(haystack)
+    }
+
+    /// Same as [`KmpPattern::contains`], running the automaton directly rather than through
+    /// the [`KmpPattern::find`] iterator.
+    pub fn is_match<H>(&self, haystack: &[H]) -> bool
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpSearch::<_, _, false>::new(self.needle, &self.lsp, haystack)
+            .next_by_skipping(item_matches, N::first_candidate)
+            .is_some()
+    }
+
+    /// Returns the position of the `n`-th (zero-based) non-overlapping match, as yielded by
+    /// [`KmpPattern::find`], or `None` if there are at most `n` matches.
+    ///
+    /// The search stops as soon as the requested match is found.
+    pub fn nth_match<H>(&self, haystack: &[H], n: usize) -> Option<usize>
+    where
+        N: KmpMatchable<H>,
+    {
+        self.find(haystack).nth(n)
+    }
+
+    /// Returns the position of the `n`-th (zero-based) overlapping match, as yielded by
+    /// [`KmpPattern::find_overlapping`], or `None` if there are at most `n` matches.
+    pub fn nth_match_overlapping<H>(&self, haystack: &[H], n: usize) -> Option<usize>
+    where
+        N: KmpMatchable<H>,
+    {
+        self.find_overlapping(haystack).nth(n)
+    }
+
+    /// Counts non-overlapping matches, as yielded by [`KmpPattern::find`], without
+    /// collecting them.
+    ///
+    /// `aa` occurs twice in `aaaaa` by this count, and four times by
+    /// [`KmpPattern::count_matches_overlapping`], which counts every occurrence.
+    ///
+    /// Runs of back-to-back matches are counted arithmetically as in
+    /// [`KmpPattern::count_matches_overlapping`], with the needle length as the period.
+    pub fn count_matches<H>(&self, haystack: &[H]) -> usize
+    where
+        N: KmpMatchable<H>,
+        H: PartialEq,
+    {
+        self.count_runs::<H, false>(haystack)
+    }
+
+    /// Counts overlapping matches, as yielded by [`KmpPattern::find_overlapping`].
+    ///
+    /// After a match, matches keep following one needle period `p` apart for as long as
+    /// the haystack repeats with period `p`, which is checked by comparing haystack items
+    /// with the items `p` before them instead of running the automaton. The matches in such
+    /// a run are then counted from its length, and the automaton resumes at its end. This
+    /// assumes that haystack items comparing equal are matched alike, and is skipped when
+    /// the border of the needle is only possibly matched.
+    ///
+    /// The result is always equal to `self.find_overlapping(haystack).count()`.
+    pub fn count_matches_overlapping<H>(&self, haystack: &[H]) -> usize
+    where
+        N: KmpMatchable<H>,
+        H: PartialEq,
+    {
+        self.count_runs::<H, true>(haystack)
+    }
+
+    fn count_runs<H, const OVERLAPPING: bool>(&self, haystack: &[H]) -> usize
+    where
+        N: KmpMatchable<H>,
+        H: PartialEq,
+    {
+        let needle_len = self.needle.len();
+        if needle_len == 0 {
+            return haystack.len() + 1;
+        }
+
+        // Needle position after a match. If the border it falls back to is guaranteed to
+        // match, every item matching a needle item also matches the item one period
+        // before it, so a window repeating the previous one a period later is a match too.
+        let border = self.lsp[needle_len - 1];
+        let (resume, periodic) = if OVERLAPPING {
+            (border.needle, border.haystack == 0)
+        } else {
+            (0, true)
+        };
+        let period = needle_len - resume;
+
+        let mut search = KmpSearch::<_, _, OVERLAPPING>::new(self.needle, &self.lsp, haystack);
+        let mut count = 0;
+
+        // The automaton is driven directly, as nothing is taken from the back and no limit
+        // is set, and the empty needle was handled above.
+        while search
+            .next_by_skipping(item_matches, N::first_candidate)
+            .is_some()
+        {
+            count += 1;
+
+            if !periodic {
+                continue;
+            }
+
+            let end = search.haystack_pos;
+            let run = common_prefix_len(&haystack[end..], &haystack[end - period..]);
+
+            count += run / period;
+            search.haystack_pos = end + run;
+            search.needle_pos = resume + run % period;
+        }
+
+        count
+    }
+}
+
+/// Length of the longest common prefix of `a` and `b`.
+fn common_prefix_len<H: PartialEq>(a: &[H], b: &[H]) -> usize {
+    // Whole chunks are compared as slices first, which is a `memcmp` for bytes.
+    const CHUNK: usize = 64;
+
+    let len = a.len().min(b.len());
+    let mut pos = 0;
+    while pos + CHUNK <= len && a[pos..pos + CHUNK] == b[pos..pos + CHUNK] {
+        pos += CHUNK;
+    }
+
+    pos + a[pos..len]
+        .iter()
+        .zip(&b[pos..len])
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::xorshift;
+    use crate::{CaselessAscii, KmpPattern, Wildcard};
+
+    fn naive_count_overlapping(needle: &[u8], haystack: &[u8]) -> usize {
+        if needle.len() > haystack.len() {
+            return 0;
+        }
+
+        (0..=haystack.len() - needle.len())
+            .filter(|&i| &haystack[i..i + needle.len()] == needle)
+            .count()
+    }
+
+    #[test]
+    fn contains() {
+        let pattern = KmpPattern::new(b"ab");
+        assert!(pattern.contains(b"xxab"));
+        assert!(!pattern.contains(b"xxa"));
+        assert!(KmpPattern::<u8>::new(b"").contains(b""));
+    }
+
+    #[test]
+    fn is_match() {
+        for (needle, haystack) in [("ab", "xxab"), ("ab", "xxa"), ("aab", "aaab"), ("", "")] {
+            let pattern = KmpPattern::new(needle.as_bytes());
+            assert_eq!(
+                pattern.is_match(haystack.as_bytes()),
+                haystack.contains(needle)
+            );
+        }
+    }
+
+    #[test]
+    fn nth_match() {
+        let pattern = KmpPattern::new(b"a");
+        assert_eq!(pattern.nth_match(b"aaaa", 0), Some(0));
+        assert_eq!(pattern.nth_match(b"aaaa", 2), Some(2));
+        assert_eq!(pattern.nth_match(b"aaaa", 4), None);
+
+        let pattern = KmpPattern::new(b"aa");
+        assert_eq!(pattern.nth_match(b"aaaaa", 1), Some(2));
+        assert_eq!(pattern.nth_match(b"aaaaa", 2), None);
+        assert_eq!(pattern.nth_match_overlapping(b"aaaaa", 2), Some(2));
+        assert_eq!(pattern.nth_match_overlapping(b"aaaaa", 3), Some(3));
+        assert_eq!(pattern.nth_match_overlapping(b"aaaaa", 4), None);
+    }
+
+    #[test]
+    fn count_matches() {
+        let pattern = KmpPattern::new(b"aa");
+        assert_eq!(pattern.count_matches(b"aaaaa"), 2);
+        assert_eq!(pattern.count_matches_overlapping(b"aaaaa"), 4);
+    }
+
+    #[test]
+    fn count_whole_haystack() {
+        for needle in [&b"a"[..], b"aa", b"abab", b"abcab"] {
+            let pattern = KmpPattern::new(needle);
+            assert_eq!(pattern.count_matches(needle), 1);
+            assert_eq!(pattern.count_matches_overlapping(needle), 1);
+        }
+    }
+
+    #[test]
+    fn count_overlapping() {
+        const TEST_CASES: &[(&[u8], &[u8])] = &[
+            (b"aa", b"aaaaa"),
+            (b"a", b"aaaaa"),
+            (b"abc", b"abcabcabc"),
+            (b"abab", b"abababababxababab"),
+            (b"aab", b"aabaabaabaaab"),
+            (b"abc", b"xyz"),
+            (b"abcdef", b"abc"),
+            (b"", b"abc"),
+            (b"aaa", b"aaaabaaaaaabaa"),
+        ];
+
+        for (needle, haystack) in TEST_CASES {
+            let pattern = KmpPattern::new(needle);
+            assert_eq!(
+                pattern.count_matches_overlapping(haystack),
+                pattern.find_overlapping(haystack).count(),
+                "needle: {:?}, haystack: {:?}",
+                needle,
+                haystack
+            );
+        }
+    }
+
+    #[test]
+    fn count_random() {
+        let mut next = xorshift(0x2545_f491);
+
+        for _ in 0..2000 {
+            let needle: Vec<u8> = (0..1 + next(4)).map(|_| b'a' + next(2) as u8).collect();
+            let haystack: Vec<u8> = (0..next(40)).map(|_| b'a' + next(2) as u8).collect();
+            let pattern = KmpPattern::new(&needle);
+
+            assert_eq!(
+                pattern.count_matches(&haystack),
+                pattern.find(&haystack).count()
+            );
+            assert_eq!(
+                pattern.count_matches_overlapping(&haystack),
+                naive_count_overlapping(&needle, &haystack)
+            );
+        }
+    }
+
+    #[test]
+    fn count_fuzzy_random() {
+        let mut next = xorshift(0x68e3_1da4);
+
+        for _ in 0..2000 {
+            let needle: Vec<_> = (0..1 + next(5))
+                .map(|_| match next(3) {
+                    0 => Wildcard::Any,
+                    i => Wildcard::Exact(b'a' + i as u8 - 1),
+                })
+                .collect();
+            let haystack: Vec<u8> = (0..next(60)).map(|_| b"aab"[next(3)]).collect();
+            let pattern = KmpPattern::new(&needle);
+
+            assert_eq!(
+                pattern.count_matches(&haystack),
+                pattern.find(&haystack).count()
+            );
+            assert_eq!(
+                pattern.count_matches_overlapping(&haystack),
+                pattern.find_overlapping(&haystack).count(),
+                "needle: {:?}, haystack: {:?}",
+                needle,
+                haystack
+            );
+
+            // Items that match alike without comparing equal end a run early.
+            let needle = CaselessAscii::needle(b"aa");
+            let haystack: Vec<u8> = (0..next(60)).map(|_| b"aAb"[next(3)]).collect();
+            let pattern = KmpPattern::new(&needle);
+            assert_eq!(
+                pattern.count_matches_overlapping(&haystack),
+                pattern.find_overlapping(&haystack).count()
+            );
+        }
+    }
+
+    #[test]
+    fn count_overlapping_long_run() {
+        let mut haystack = vec![b'a'; 1 << 20];
+        haystack[1000] = b'b';
+        haystack.extend_from_slice(b"ba");
+
+        for needle in [&b"aa"[..], b"aaa", b"ab", b"aba"] {
+            let pattern = KmpPattern::new(needle);
+            assert_eq!(
+                pattern.count_matches_overlapping(&haystack),
+                naive_count_overlapping(needle, &haystack)
+            );
+        }
+    }
+}
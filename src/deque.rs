@@ -0,0 +1,110 @@
+use alloc::collections::VecDeque;
+
+use crate::segments::KmpSegmentsSearch;
+use crate::{KmpMatchable, KmpPattern};
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Non-overlapping search over a `VecDeque` without making it contiguous.
+    ///
+    /// Positions are logical indices into `haystack`, as used by [`VecDeque::get`]. The two
+    /// internal slices of the deque are searched in place as in
+    /// [`KmpPattern::find_segments`].
+    pub fn find_deque<H>(&'a self, haystack: &'a VecDeque<H>) -> impl Iterator<Item = usize> + 'a
+    where
+        N: KmpMatchable<H>,
+    {
+        let (front, back) = haystack.as_slices();
+        KmpSegmentsSearch::<_, _, _, false>::new(self.needle, &self.lsp, [front, back])
+    }
+
+    /// Overlapping counterpart of [`KmpPattern::find_deque`].
+    pub fn find_deque_overlapping<H>(
+        &'a self,
+        haystack: &'a VecDeque<H>,
+    ) -> impl Iterator<Item = usize> + 'a
+    where
+        N: KmpMatchable<H>,
+    {
+        let (front, back) = haystack.as_slices();
+        KmpSegmentsSearch::<_, _, _, true>::new(self.needle, &self.lsp, [front, back])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::VecDeque;
+
+    use crate::test_util::{wildcard_needle, xorshift};
+    use crate::KmpPattern;
+
+    /// Deque of `items` whose internal slices are split after `split` items.
+    fn wrapped(items: &[u8], split: usize) -> VecDeque<u8> {
+        let mut deque = VecDeque::with_capacity(items.len());
+        deque.extend(&items[split..]);
+        for &item in items[..split].iter().rev() {
+            deque.push_front(item);
+        }
+        deque
+    }
+
+    #[test]
+    fn across_wrap_point() {
+        let haystack = wrapped(b"xxabcabcabxx", 6);
+        assert_eq!(haystack.as_slices(), (&b"xxabca"[..], &b"bcabxx"[..]));
+
+        let pattern = KmpPattern::new(b"abcab");
+        assert_eq!(pattern.find_deque(&haystack).collect::<Vec<_>>(), [2]);
+        assert_eq!(
+            pattern
+                .find_deque_overlapping(&haystack)
+                .collect::<Vec<_>>(),
+            [2, 5]
+        );
+    }
+
+    #[test]
+    fn empty_needle() {
+        let haystack = wrapped(b"abc", 2);
+        let pattern = KmpPattern::<u8>::new(b"");
+        assert_eq!(
+            pattern.find_deque(&haystack).collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn matches_contiguous() {
+        let mut next = xorshift(0xc2b2_ae35);
+
+        for needle in [&b"a"[..], b"aa", b"aba", b"a?a", b"?b", b"ab?ab", b"b??b"] {
+            let needle = wildcard_needle(needle);
+            let pattern = KmpPattern::new(&needle);
+
+            for _ in 0..200 {
+                let items: Vec<u8> = (0..next(24)).map(|_| b"ab"[next(2)]).collect();
+                let split = next(items.len() + 1);
+                let haystack = wrapped(&items, split);
+                if split > 0 {
+                    assert_eq!(haystack.as_slices().0, &items[..split]);
+                }
+
+                assert_eq!(
+                    pattern.find_deque(&haystack).collect::<Vec<_>>(),
+                    pattern.all_matches(&items),
+                    "needle: {:?}, haystack: {:?}",
+                    needle,
+                    haystack.as_slices()
+                );
+                assert_eq!(
+                    pattern
+                        .find_deque_overlapping(&haystack)
+                        .collect::<Vec<_>>(),
+                    pattern.all_matches_overlapping(&items),
+                    "needle: {:?}, haystack: {:?}",
+                    needle,
+                    haystack.as_slices()
+                );
+            }
+        }
+    }
+}
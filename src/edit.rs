@@ -0,0 +1,146 @@
+use alloc::vec::Vec;
+
+use crate::{KmpMatchable, KmpPattern};
+
+/// A change to a haystack: `deleted` items at `pos` were replaced by `inserted` new items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edit {
+    pub pos: usize,
+    pub deleted: usize,
+    pub inserted: usize,
+}
+
+impl Edit {
+    pub fn insert(pos: usize, len: usize) -> Self {
+        Self {
+            pos,
+            deleted: 0,
+            inserted: len,
+        }
+    }
+
+    pub fn delete(pos: usize, len: usize) -> Self {
+        Self {
+            pos,
+            deleted: len,
+            inserted: 0,
+        }
+    }
+}
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Recomputes overlapping matches after `edit` was applied to the haystack.
+    ///
+    /// `old_matches` must be the overlapping matches of the haystack before the edit and
+    /// `haystack` is the edited one. Matches entirely outside the edited region are kept
+    /// (shifted if they come after it), and only the edited region plus `needle.len() - 1`
+    /// items on each side is rescanned. The result equals
+    /// `self.find_overlapping(haystack).collect()`.
+    pub fn update_matches<H>(&self, old_matches: &[usize], haystack: &[H], edit: Edit) -> Vec<usize>
+    where
+        N: KmpMatchable<H>,
+    {
+        let needle_len = self.needle.len();
+        if needle_len == 0 {
+            return (0..=haystack.len()).collect();
+        }
+
+        let lo = edit.pos.saturating_sub(needle_len - 1);
+        let hi = haystack
+            .len()
+            .min(edit.pos + edit.inserted + needle_len - 1);
+
+        let before = old_matches
+            .iter()
+            .copied()
+            .take_while(|&pos| pos + needle_len <= edit.pos);
+
+        let window = if lo < hi {
+            &haystack[lo..hi]
+        } else {
+            &haystack[..0]
+        };
+        let rescanned = self.find_overlapping(window).map(|pos| pos + lo);
+
+        let after = old_matches
+            .iter()
+            .copied()
+            .skip_while(|&pos| pos < edit.pos + edit.deleted)
+            .map(|pos| pos - edit.deleted + edit.inserted);
+
+        before.chain(rescanned).chain(after).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::xorshift;
+    use crate::{Edit, KmpPattern};
+
+    fn apply(haystack: &[u8], edit: Edit, insertion: &[u8]) -> Vec<u8> {
+        let mut edited = haystack[..edit.pos].to_vec();
+        edited.extend_from_slice(insertion);
+        edited.extend_from_slice(&haystack[edit.pos + edit.deleted..]);
+        edited
+    }
+
+    #[test]
+    fn insert_and_delete() {
+        let pattern = KmpPattern::new(b"aba");
+        let haystack = b"abaxaba";
+        let old: Vec<_> = pattern.find_overlapping(haystack).collect();
+
+        let edit = Edit::insert(3, 2);
+        let edited = apply(haystack, edit, b"ba");
+        assert_eq!(
+            pattern.update_matches(&old, &edited, edit),
+            pattern.find_overlapping(&edited).collect::<Vec<_>>()
+        );
+
+        let edit = Edit::delete(3, 1);
+        let edited = apply(haystack, edit, b"");
+        assert_eq!(pattern.update_matches(&old, &edited, edit), [0, 3]);
+    }
+
+    #[test]
+    fn matches_full_rescan() {
+        let mut next = xorshift(0x2545_f491);
+
+        for needle in [&b"a"[..], b"ab", b"aab", b"abab", b"bbb"] {
+            let pattern = KmpPattern::new(needle);
+
+            for _ in 0..200 {
+                let haystack: Vec<u8> = (0..next(24)).map(|_| b"ab"[next(2)]).collect();
+                let pos = next(haystack.len() + 1);
+                let deleted = next(haystack.len() - pos + 1);
+                let insertion: Vec<u8> = (0..next(5)).map(|_| b"ab"[next(2)]).collect();
+                let edit = Edit {
+                    pos,
+                    deleted,
+                    inserted: insertion.len(),
+                };
+
+                let old: Vec<_> = pattern.find_overlapping(&haystack).collect();
+                let edited = apply(&haystack, edit, &insertion);
+
+                assert_eq!(
+                    pattern.update_matches(&old, &edited, edit),
+                    pattern.find_overlapping(&edited).collect::<Vec<_>>(),
+                    "needle: {:?}, haystack: {:?}, edit: {:?}",
+                    needle,
+                    haystack,
+                    edit
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn empty_needle() {
+        let pattern = KmpPattern::<u8>::new(b"");
+        assert_eq!(
+            pattern.update_matches(&[0, 1], b"xy", Edit::insert(0, 1)),
+            [0, 1, 2]
+        );
+    }
+}
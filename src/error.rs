@@ -0,0 +1,44 @@
+use core::fmt;
+
+/// Reason a pattern could not be built by [`KmpPattern::try_new`](crate::KmpPattern::try_new),
+/// [`KmpPattern::new_in`](crate::KmpPattern::new_in) or deserialization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum KmpError {
+    /// The item at `index` reports that no haystack item can match it, so the pattern can
+    /// never match.
+    UnmatchableItem { index: usize },
+    /// The item at `index` is not guaranteed to match whatever it matches itself, so its
+    /// [`KmpSearchable`](crate::KmpSearchable) implementation is inconsistent and the table
+    /// built from it cannot be trusted.
+    InconsistentItem { index: usize },
+    /// The table buffer has fewer than `needed` items, one per needle item.
+    TableTooSmall { needed: usize },
+    /// A deserialized or supplied table does not have one item per needle item with offsets
+    /// in range.
+    InvalidTable,
+}
+
+impl fmt::Display for KmpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KmpError::UnmatchableItem { index } => {
+                write!(f, "needle item {} cannot match any haystack item", index)
+            }
+            KmpError::InconsistentItem { index } => {
+                write!(f, "needle item {} is not guaranteed to match itself", index)
+            }
+            KmpError::TableTooSmall { needed } => {
+                write!(
+                    f,
+                    "table buffer is shorter than the needle of {} items",
+                    needed
+                )
+            }
+            KmpError::InvalidTable => f.write_str("table does not fit the needle"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for KmpError {}
@@ -0,0 +1,285 @@
+use alloc::vec::Vec;
+
+use crate::{KmpMatchable, KmpPattern, KmpSearchable};
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Collects the positions yielded by [`KmpPattern::find`].
+    pub fn all_matches<H>(&self, haystack: &[H]) -> Vec<usize>
+    where
+        N: KmpMatchable<H>,
+    {
+        self.find(haystack).collect()
+    }
+
+    /// Collects at most `n` of the positions yielded by [`KmpPattern::find`], scanning the
+    /// haystack only up to the end of the `n`-th match.
+    pub fn take_matches<H>(&self, haystack: &[H], n: usize) -> Vec<usize>
+    where
+        N: KmpMatchable<H>,
+    {
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let search = self.find(haystack);
+        let capacity = search.size_hint().1.map_or(n, |upper| upper.min(n));
+
+        let mut matches = Vec::with_capacity(capacity);
+        matches.extend(search.take(n));
+        matches
+    }
+
+    /// Collects the positions yielded by [`KmpPattern::find_overlapping`].
+    pub fn all_matches_overlapping<H>(&self, haystack: &[H]) -> Vec<usize>
+    where
+        N: KmpMatchable<H>,
+    {
+        self.find_overlapping(haystack).collect()
+    }
+
+    /// Replaces the contents of `out` with the positions yielded by [`KmpPattern::find`].
+    ///
+    /// `out` is cleared first, so its allocation can be reused across searches.
+    pub fn find_into<H>(&self, haystack: &[H], out: &mut Vec<usize>)
+    where
+        N: KmpMatchable<H>,
+    {
+        out.clear();
+        out.extend(self.find(haystack));
+    }
+
+    /// Replaces the contents of `out` with the positions yielded by
+    /// [`KmpPattern::find_overlapping`].
+    ///
+    /// `out` is cleared first, as with [`KmpPattern::find_into`].
+    pub fn find_overlapping_into<H>(&self, haystack: &[H], out: &mut Vec<usize>)
+    where
+        N: KmpMatchable<H>,
+    {
+        out.clear();
+        out.extend(self.find_overlapping(haystack));
+    }
+
+    /// Non-overlapping search keeping only matches whose next haystack item satisfies
+    /// `pred`.
+    ///
+    /// A match ending exactly at the end of the haystack has no following item and is never
+    /// reported.
+    pub fn find_followed_by<H, F>(
+        &'a self,
+        haystack: &'a [H],
+        pred: F,
+    ) -> impl Iterator<Item = usize> + 'a
+    where
+        N: KmpMatchable<H>,
+        F: Fn(&H) -> bool + 'a,
+    {
+        let needle_len = self.needle.len();
+
+        self.find(haystack)
+            .filter(move |&pos| haystack.get(pos + needle_len).is_some_and(&pred))
+    }
+
+    /// Number of haystack items before the first match. Stops at the first match.
+    pub fn first_match_offset<H>(&self, haystack: &[H]) -> Option<usize>
+    where
+        N: KmpMatchable<H>,
+    {
+        self.find(haystack).next()
+    }
+
+    /// Start and matched subslice of the first match. Stops at the first match.
+    pub fn first_match<'h, H>(&self, haystack: &'h [H]) -> Option<(usize, &'h [H])>
+    where
+        N: KmpMatchable<H>,
+    {
+        let start = self.find(haystack).next()?;
+        Some((start, &haystack[start..start + self.needle.len()]))
+    }
+
+    /// Number of haystack items after the end of the last match.
+    ///
+    /// The last match is the rightmost occurrence, so overlapping matches are considered.
+    /// It is found with [`KmpPattern::rfind`], which scans from the end and stops there.
+    pub fn last_match_end_offset<H>(&self, haystack: &[H]) -> Option<usize>
+    where
+        N: KmpSearchable + KmpMatchable<H>,
+    {
+        let last = self.rfind(haystack)?;
+        Some(haystack.len() - last - self.needle.len())
+    }
+
+    /// Collects overlapping matches as `(start, len)` pairs, sorted by start and then by
+    /// length, without duplicates.
+    ///
+    /// Match lengths are currently constant: every match spans exactly `needle.len()` items,
+    /// even for needles containing wildcards. Each start is therefore reported once, and the
+    /// ascending order of [`KmpPattern::find_overlapping`] already is the canonical order, so no
+    /// sorting is performed.
+    pub fn find_overlapping_sorted<H>(&self, haystack: &[H]) -> Vec<(usize, usize)>
+    where
+        N: KmpMatchable<H>,
+    {
+        let needle_len = self.needle.len();
+        self.find_overlapping(haystack)
+            .map(|pos| (pos, needle_len))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+    use alloc::vec::Vec;
+
+    use crate::test_util::{wildcard_needle, xorshift};
+    use crate::{KmpMatchable, KmpPattern, Wildcard};
+
+    #[test]
+    fn find_into() {
+        let pattern = KmpPattern::new(b"aa");
+        let mut out = Vec::new();
+
+        pattern.find_into(b"aaaXaa", &mut out);
+        assert_eq!(out, [0, 4]);
+
+        pattern.find_into(b"Xaa", &mut out);
+        assert_eq!(out, [1]);
+
+        pattern.find_overlapping_into(b"aaaXaa", &mut out);
+        assert_eq!(out, [0, 1, 4]);
+
+        pattern.find_overlapping_into(b"X", &mut out);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn take_matches() {
+        let pattern = KmpPattern::new(b"a");
+        assert_eq!(pattern.take_matches(b"aaaa", 2), [0, 1]);
+        assert_eq!(pattern.take_matches(b"aaaa", 10), [0, 1, 2, 3]);
+        assert!(pattern.take_matches(b"aaaa", 0).is_empty());
+
+        // The capacity is bounded by the most matches the haystack can hold.
+        let pattern = KmpPattern::new(b"aa");
+        assert_eq!(pattern.take_matches(b"aaaaa", usize::MAX), [0, 2]);
+    }
+
+    #[test]
+    fn first_match() {
+        let pattern = KmpPattern::new(b"ab");
+        let haystack = b"xxabyab";
+
+        let (start, slice) = pattern.first_match(haystack).unwrap();
+        assert_eq!(start, 2);
+        assert_eq!(slice, b"ab");
+        assert!(core::ptr::eq(slice.as_ptr(), &haystack[2]));
+
+        assert_eq!(pattern.first_match(b"xxa"), None);
+        assert_eq!(
+            KmpPattern::<u8>::new(b"").first_match(b"abc"),
+            Some((0, &b""[..]))
+        );
+    }
+
+    #[test]
+    fn all_matches() {
+        let pattern = KmpPattern::new(b"aa");
+        assert_eq!(pattern.all_matches(b"aaaaa"), [0, 2]);
+        assert_eq!(pattern.all_matches_overlapping(b"aaaaa"), [0, 1, 2, 3]);
+        assert!(pattern.all_matches(b"ab").is_empty());
+    }
+
+    #[test]
+    fn followed_by() {
+        let pattern = KmpPattern::new(b"ab");
+        let found: Vec<_> = pattern
+            .find_followed_by(b"ab1abxab2ab", u8::is_ascii_digit)
+            .collect();
+        assert_eq!(found, [0, 6]);
+    }
+
+    #[test]
+    fn followed_by_at_end() {
+        let pattern = KmpPattern::new(b"ab");
+        let found: Vec<_> = pattern.find_followed_by(b"xab", |_| true).collect();
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn match_offsets() {
+        let pattern = KmpPattern::new(b"aa");
+        assert_eq!(pattern.first_match_offset(b"xxaaay"), Some(2));
+        assert_eq!(pattern.last_match_end_offset(b"xxaaay"), Some(1));
+        assert_eq!(pattern.first_match_offset(b"xyz"), None);
+        assert_eq!(pattern.last_match_end_offset(b"xyz"), None);
+        assert_eq!(pattern.last_match_end_offset(b"aa"), Some(0));
+
+        let wildcard = KmpPattern::new(&[Wildcard::Exact(b'a'), Wildcard::Any]);
+        assert_eq!(wildcard.last_match_end_offset(b"xaaab"), Some(0));
+        assert_eq!(wildcard.last_match_end_offset(b"aaxb"), Some(1));
+
+        let empty = KmpPattern::<u8>::new(b"");
+        assert_eq!(empty.first_match_offset(b"abc"), Some(0));
+        assert_eq!(empty.last_match_end_offset(b"abc"), Some(0));
+    }
+
+    #[test]
+    fn overlapping_sorted() {
+        let pattern = KmpPattern::new(b"aba");
+        assert_eq!(
+            pattern.find_overlapping_sorted(b"abababxaba"),
+            [(0, 3), (2, 3), (7, 3)]
+        );
+
+        // A wildcard needle still yields a single length per start.
+        let wildcard = KmpPattern::new(&[Wildcard::Exact(b'a'), Wildcard::Any, Wildcard::Any]);
+        assert_eq!(
+            wildcard.find_overlapping_sorted(b"aaaba"),
+            [(0, 3), (1, 3), (2, 3)]
+        );
+
+        let empty = KmpPattern::<u8>::new(b"");
+        assert_eq!(
+            empty.find_overlapping_sorted(b"ab"),
+            [(0, 0), (1, 0), (2, 0)]
+        );
+    }
+
+    #[test]
+    fn overlapping_sorted_wildcards() {
+        let mut next = xorshift(0x2f6b_8e91);
+
+        for needle in [&b"a?a"[..], b"?a", b"a??", b"??", b"?a?a", b"a?ab?"] {
+            let needle = wildcard_needle(needle);
+            let pattern = KmpPattern::new(&needle);
+
+            for _ in 0..200 {
+                let haystack: Vec<u8> = (0..next(20)).map(|_| b"ab"[next(2)]).collect();
+                let found = pattern.find_overlapping_sorted(&haystack);
+
+                // Strictly ascending pairs are sorted by start, then by length, and unique.
+                assert!(found.windows(2).all(|pair| pair[0] < pair[1]));
+
+                let expected: BTreeSet<_> = (0..=haystack.len().saturating_sub(needle.len()))
+                    .filter(|&start| {
+                        haystack.len() >= needle.len()
+                            && needle
+                                .iter()
+                                .zip(&haystack[start..])
+                                .all(|(item, b)| item.match_haystack(b))
+                    })
+                    .map(|start| (start, needle.len()))
+                    .collect();
+                assert_eq!(found, expected.into_iter().collect::<Vec<_>>());
+            }
+        }
+    }
+
+    #[test]
+    fn followed_by_empty_needle() {
+        let pattern = KmpPattern::<u8>::new(b"");
+        let found: Vec<_> = pattern.find_followed_by(b"a1", |&b| b == b'1').collect();
+        assert_eq!(found, [1]);
+    }
+}
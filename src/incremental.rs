@@ -0,0 +1,250 @@
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+use crate::{KmpMatchable, KmpPattern, KmpSearch, KmpTableItem};
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Non-overlapping push-style search over input arriving in chunks.
+    pub fn incremental<H>(&'a self) -> KmpIncremental<'a, N, H, false>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpIncremental::new(self.needle, &self.lsp)
+    }
+
+    /// Overlapping push-style search over input arriving in chunks.
+    pub fn incremental_overlapping<H>(&'a self) -> KmpIncremental<'a, N, H, true>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpIncremental::new(self.needle, &self.lsp)
+    }
+}
+
+/// Push-style search: input is fed chunk by chunk and matches are reported as absolute
+/// offsets from the start of the stream.
+///
+/// The automaton state and the items of the pending partial match are carried over
+/// between chunks, so matches split across chunks are found.
+pub struct KmpIncremental<'a, N, H, const OVERLAPPING: bool> {
+    pub(crate) needle: &'a [N],
+    lsp: &'a [KmpTableItem],
+    pub(crate) buffer: Vec<H>,
+    pub(crate) offset: u64,
+    needle_pos: usize,
+    haystack_pos: usize,
+}
+
+impl<'a, N, H, const OVERLAPPING: bool> KmpIncremental<'a, N, H, OVERLAPPING> {
+    pub fn new(needle: &'a [N], lsp: &'a [KmpTableItem]) -> Self {
+        Self {
+            needle,
+            lsp,
+            buffer: Vec::new(),
+            offset: 0,
+            needle_pos: 0,
+            haystack_pos: 0,
+        }
+    }
+
+    /// Total number of items fed so far.
+    pub fn stream_len(&self) -> u64 {
+        self.offset + self.buffer.len() as u64
+    }
+
+    /// Automaton state as `(needle_pos, haystack_pos)`: the length of the pending partial
+    /// match and the number of stream items consumed so far.
+    pub fn state(&self) -> (usize, u64) {
+        (self.needle_pos, self.offset + self.haystack_pos as u64)
+    }
+
+    /// Drops buffered items that can no longer be part of a match.
+    pub(crate) fn compact(&mut self) {
+        let keep_from = self.haystack_pos.min(self.buffer.len()) - self.needle_pos;
+        self.buffer.drain(..keep_from);
+        self.offset += keep_from as u64;
+        self.haystack_pos -= keep_from;
+    }
+
+    /// Finds the next match in the buffered input. The empty needle's match at the very end
+    /// of the stream is left to the caller, as more input may follow.
+    pub(crate) fn next_match(&mut self) -> Option<u64>
+    where
+        N: KmpMatchable<H>,
+    {
+        if self.needle.is_empty() {
+            if self.haystack_pos >= self.buffer.len() {
+                return None;
+            }
+
+            self.haystack_pos += 1;
+            return Some(self.offset + self.haystack_pos as u64 - 1);
+        }
+
+        let mut search: KmpSearch<'_, N, H, OVERLAPPING> = KmpSearch {
+            needle_pos: self.needle_pos,
+            haystack_pos: self.haystack_pos,
+            ..KmpSearch::new(self.needle, self.lsp, &self.buffer)
+        };
+
+        let found = search.next();
+        self.needle_pos = search.needle_pos;
+        self.haystack_pos = search.haystack_pos;
+
+        found.map(|pos| self.offset + pos as u64)
+    }
+
+    /// Appends `chunk` to the stream and returns the matches it completes.
+    ///
+    /// Matches left unconsumed in the returned iterator are reported by the next call.
+    pub fn feed(&mut self, chunk: &[H]) -> KmpFeed<'_, 'a, N, H, OVERLAPPING>
+    where
+        N: KmpMatchable<H>,
+        H: Clone,
+    {
+        self.compact();
+        self.buffer.extend_from_slice(chunk);
+
+        KmpFeed { incremental: self }
+    }
+
+    /// Starts a new stream, dropping the buffered input and any partial match while keeping
+    /// the allocation of the buffer.
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.offset = 0;
+        self.needle_pos = 0;
+        self.haystack_pos = 0;
+    }
+
+    /// Ends the stream, returning the matches not reported yet.
+    ///
+    /// For the empty needle this includes the match at the end of the stream.
+    pub fn finish(mut self) -> Vec<u64>
+    where
+        N: KmpMatchable<H>,
+    {
+        let mut remaining: Vec<_> = core::iter::from_fn(|| self.next_match()).collect();
+
+        if self.needle.is_empty() {
+            remaining.push(self.stream_len());
+        }
+
+        remaining
+    }
+}
+
+/// Iterator over the matches completed by a [`KmpIncremental::feed`] call.
+pub struct KmpFeed<'s, 'a, N, H, const OVERLAPPING: bool> {
+    incremental: &'s mut KmpIncremental<'a, N, H, OVERLAPPING>,
+}
+
+impl<'s, 'a, N, H, const OVERLAPPING: bool> Iterator for KmpFeed<'s, 'a, N, H, OVERLAPPING>
+where
+    N: KmpMatchable<H>,
+{
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.incremental.next_match()
+    }
+}
+
+impl<'s, 'a, N, H, const OVERLAPPING: bool> FusedIterator for KmpFeed<'s, 'a, N, H, OVERLAPPING> where
+    N: KmpMatchable<H>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::KmpPattern;
+
+    #[test]
+    fn split_match() {
+        let pattern = KmpPattern::new(b"abc");
+        let mut incremental = pattern.incremental();
+
+        let mut found: Vec<u64> = vec![];
+        for chunk in [&b"ab"[..], b"cab", b"c"] {
+            found.extend(incremental.feed(chunk));
+        }
+        assert_eq!(found, [0, 3]);
+        assert!(incremental.finish().is_empty());
+    }
+
+    #[test]
+    fn matches_slice_search() {
+        const TEST_CASES: &[(&[u8], &[u8])] = &[
+            (b"aa", b"aaaaaaa"),
+            (b"abab", b"abababxababab"),
+            (b"aab", b"aaabaabaaabaab"),
+            (b"abc", b"xyz"),
+        ];
+
+        for (needle, haystack) in TEST_CASES {
+            let pattern = KmpPattern::new(needle);
+
+            for chunk_len in 1..=haystack.len() {
+                let mut incremental = pattern.incremental();
+                let mut overlapping = pattern.incremental_overlapping();
+                let mut found = vec![];
+                let mut found_overlapping = vec![];
+
+                for chunk in haystack.chunks(chunk_len) {
+                    found.extend(incremental.feed(chunk).map(|pos| pos as usize));
+                    found_overlapping.extend(overlapping.feed(chunk).map(|pos| pos as usize));
+                }
+
+                assert_eq!(found, pattern.find(haystack).collect::<Vec<_>>());
+                assert_eq!(
+                    found_overlapping,
+                    pattern.find_overlapping(haystack).collect::<Vec<_>>()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn state() {
+        let pattern = KmpPattern::new(b"abcd");
+        let mut incremental = pattern.incremental();
+
+        for chunk in [&b"xab"[..], b"cxa", b"bc"] {
+            assert!(incremental.feed(chunk).next().is_none());
+        }
+        assert_eq!(incremental.state(), (3, 8));
+    }
+
+    #[test]
+    fn unconsumed_matches_carry_over() {
+        let pattern = KmpPattern::new(b"a");
+        let mut incremental = pattern.incremental();
+
+        assert_eq!(incremental.feed(b"aa").next(), Some(0));
+        assert_eq!(incremental.feed(b"a").collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(incremental.stream_len(), 3);
+    }
+
+    #[test]
+    fn finish_empty_needle() {
+        let pattern = KmpPattern::<u8>::new(b"");
+        let mut incremental = pattern.incremental();
+
+        assert_eq!(incremental.feed(b"ab").collect::<Vec<_>>(), [0, 1]);
+        assert_eq!(incremental.feed(b"c").collect::<Vec<_>>(), [2]);
+        assert_eq!(incremental.finish(), [3]);
+    }
+
+    #[test]
+    fn reset() {
+        let pattern = KmpPattern::new(b"abc");
+        let mut incremental = pattern.incremental();
+
+        assert!(incremental.feed(b"xxab").next().is_none());
+        incremental.reset();
+        assert_eq!(incremental.state(), (0, 0));
+        assert!(incremental.feed(b"c").next().is_none());
+        assert_eq!(incremental.feed(b"abc").collect::<Vec<_>>(), [1]);
+        assert_eq!(incremental.stream_len(), 4);
+    }
+}
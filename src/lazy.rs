@@ -0,0 +1,158 @@
+use core::iter::FusedIterator;
+
+use crate::{KmpIncremental, KmpMatchable, KmpPattern, KmpTableItem};
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Non-overlapping search over items pulled lazily from `haystack`, yielding their
+    /// indices.
+    pub fn find_iter<I>(&'a self, haystack: I) -> KmpIterSearch<'a, N, I::IntoIter, false>
+    where
+        I: IntoIterator,
+        N: KmpMatchable<I::Item>,
+    {
+        KmpIterSearch::new(self.needle, &self.lsp, haystack.into_iter())
+    }
+
+    /// Overlapping search over items pulled lazily from `haystack`.
+    pub fn find_iter_overlapping<I>(
+        &'a self,
+        haystack: I,
+    ) -> KmpIterSearch<'a, N, I::IntoIter, true>
+    where
+        I: IntoIterator,
+        N: KmpMatchable<I::Item>,
+    {
+        KmpIterSearch::new(self.needle, &self.lsp, haystack.into_iter())
+    }
+}
+
+/// Search over an iterator, pulling one item at a time.
+///
+/// A match is reported as soon as its last item has been pulled. Only the items of the
+/// current partial match are needed to carry on the search, so the buffer is compacted
+/// once it holds twice the needle length.
+pub struct KmpIterSearch<'a, N, I: Iterator, const OVERLAPPING: bool> {
+    state: KmpIncremental<'a, N, I::Item, OVERLAPPING>,
+    haystack: I,
+    finished: bool,
+}
+
+impl<'a, N, I: Iterator, const OVERLAPPING: bool> KmpIterSearch<'a, N, I, OVERLAPPING> {
+    pub fn new(needle: &'a [N], lsp: &'a [KmpTableItem], haystack: I) -> Self {
+        Self {
+            state: KmpIncremental::new(needle, lsp),
+            haystack,
+            finished: false,
+        }
+    }
+
+    /// Pulls the next item, returning `false` at the end of the haystack.
+    fn refill(&mut self) -> bool {
+        let Some(item) = self.haystack.next() else {
+            return false;
+        };
+
+        if self.state.buffer.len() >= 2 * self.state.needle.len().max(1) {
+            self.state.compact();
+        }
+
+        self.state.buffer.push(item);
+        true
+    }
+}
+
+impl<'a, N, I, const OVERLAPPING: bool> Iterator for KmpIterSearch<'a, N, I, OVERLAPPING>
+where
+    N: KmpMatchable<I::Item>,
+    I: Iterator,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pos) = self.state.next_match() {
+                return Some(pos as usize);
+            }
+
+            if !self.refill() {
+                if self.finished {
+                    return None;
+                }
+
+                // The empty needle also matches once at the very end of the haystack.
+                self.finished = true;
+                if self.state.needle.is_empty() {
+                    return Some(self.state.stream_len() as usize);
+                }
+
+                return None;
+            }
+        }
+    }
+}
+
+impl<'a, N, I, const OVERLAPPING: bool> FusedIterator for KmpIterSearch<'a, N, I, OVERLAPPING>
+where
+    N: KmpMatchable<I::Item>,
+    I: Iterator,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use core::iter;
+
+    use crate::test_util::wildcard_needle;
+    use crate::KmpPattern;
+
+    #[test]
+    fn repeated_input() {
+        let pattern = KmpPattern::new(b"abab");
+        let haystack = iter::repeat_n(*b"abx", 2).flatten().chain(*b"ababab");
+
+        assert_eq!(pattern.find_iter(haystack.clone()).collect::<Vec<_>>(), [6]);
+        assert_eq!(
+            pattern.find_iter_overlapping(haystack).collect::<Vec<_>>(),
+            [6, 8]
+        );
+    }
+
+    #[test]
+    fn infinite_input() {
+        let pattern = KmpPattern::new(b"ba");
+        let haystack = iter::repeat_n(b'a', 1000).chain(iter::repeat(*b"ab").flatten());
+
+        assert_eq!(
+            pattern.find_iter(haystack).take(3).collect::<Vec<_>>(),
+            [1001, 1003, 1005]
+        );
+    }
+
+    #[test]
+    fn matches_slice_search() {
+        const TEST_CASES: &[(&[u8], &[u8])] = &[
+            (b"aa", b"aaaaaaa"),
+            (b"a?a", b"abaaacaaba"),
+            (b"?b", b"bbabcb"),
+            (b"aab", b"aaabaabaaabaab"),
+            (b"abc", b"xyz"),
+            (b"", b"abc"),
+            (b"abc", b""),
+        ];
+
+        for (needle, haystack) in TEST_CASES {
+            let needle = wildcard_needle(needle);
+            let pattern = KmpPattern::new(&needle);
+            let lazy = haystack.iter().copied();
+
+            assert_eq!(
+                pattern.find_iter(lazy.clone()).collect::<Vec<_>>(),
+                pattern.find(haystack).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                pattern.find_iter_overlapping(lazy).collect::<Vec<_>>(),
+                pattern.find_overlapping(haystack).collect::<Vec<_>>()
+            );
+        }
+    }
+}
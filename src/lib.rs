@@ -1,16 +1,156 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+mod analysis;
+mod anchored;
+#[cfg(feature = "tokio")]
+mod async_read;
+mod automaton;
+mod builder;
+#[cfg(feature = "unicode-case")]
+mod case_fold;
+#[cfg(feature = "std")]
+mod channel;
+mod chars;
+mod closure;
+mod count;
+mod deque;
+mod edit;
+mod error;
+mod find;
+mod incremental;
+mod lazy;
+mod matchers;
+mod matches;
+mod multi;
+mod owned;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod position;
+mod ranges;
+mod replace;
+mod reverse;
+mod search_builder;
+mod segments;
+#[cfg(feature = "snapshots")]
+mod snapshot;
+mod split;
+mod stats;
+#[cfg(feature = "std")]
+mod stream;
+
+#[cfg(feature = "tokio")]
+pub use async_read::KmpAsyncSearch;
+pub use automaton::AutomatonNode;
+pub use builder::KmpNeedleBuilder;
+#[cfg(feature = "unicode-case")]
+pub use case_fold::CaseFold;
+pub use chars::KmpStrSearch;
+pub use closure::{KmpClosurePattern, KmpClosureSearch};
+pub use edit::Edit;
+pub use error::KmpError;
+pub use incremental::{KmpFeed, KmpIncremental};
+pub use lazy::KmpIterSearch;
+pub use matchers::{CaselessAscii, CharClass, NotEqual, PatternElement, Wildcard};
+pub use matches::{KmpMatches, Match};
+pub use multi::{KmpMultiPattern, KmpMultiSearch};
+pub use owned::KmpOwnedPattern;
+pub use position::{LineColumn, LineIndex};
+pub use ranges::KmpRanges;
+pub use search_builder::{KmpConfiguredSearch, KmpSearchBuilder, Overlap};
+#[cfg(feature = "snapshots")]
+pub use snapshot::{KmpSnapshots, KmpState};
+pub use split::{KmpSplit, Segment};
+pub use stats::KmpCountedSearch;
+#[cfg(feature = "std")]
+pub use stream::{KmpBufReadSearch, KmpStreamSearch};
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::ops::Range;
+
+use reverse::KmpBack;
+
+/// Relation between needle items, used to build the table.
+///
+/// `is_match_possible` tells whether some haystack item can match both `self` and `other`,
+/// and `is_match_guaranteed` whether every haystack item matching `self` also matches
+/// `other`.
 pub trait KmpSearchable {
     fn is_match_possible(&self, other: &Self) -> bool;
 
     fn is_match_guaranteed(&self, other: &Self) -> bool;
 }
 
+/// Comparison of a needle item with a haystack item of type `H`.
+///
+/// The haystack type need not be the needle type: a needle of tokens standing for classes
+/// of bytes can be searched for in a `&[u8]` by implementing `KmpMatchable<u8>` for the token
+/// type, together with [`KmpSearchable`] relating tokens to each other. A blanket
+/// implementation over a separate conversion trait is not possible, as it would overlap with
+/// the implementations for primitive types.
 pub trait KmpMatchable<H> {
     fn match_haystack(&self, other: &H) -> bool;
+
+    /// Index of the first item of `haystack` that `self` can match, or `haystack.len()` if
+    /// there is none. Searches call this while no partial match is pending to skip ahead.
+    ///
+    /// Returning any smaller index is correct, only slower. The default returns `0` and so
+    /// never skips.
+    fn first_candidate(&self, haystack: &[H]) -> usize {
+        let _ = haystack;
+        0
+    }
+
+    /// Cheap precheck consulted by searches before [`KmpMatchable::match_haystack`]:
+    /// returning `true` rejects `other` without calling `match_haystack`.
+    ///
+    /// It must only return `true` for items that `match_haystack` rejects. The default
+    /// rejects nothing.
+    fn quick_reject(&self, other: &H) -> bool {
+        let _ = other;
+        false
+    }
+}
+
+/// Compares a needle item with a haystack item as searches do, trying
+/// [`KmpMatchable::quick_reject`] first.
+fn item_matches<N: KmpMatchable<H>, H>(needle: &N, item: &H) -> bool {
+    !needle.quick_reject(item) && needle.match_haystack(item)
+}
+
+trait KmpPrimitive: PartialEq + Sized {
+    fn first_candidate(&self, haystack: &[Self]) -> usize {
+        let _ = haystack;
+        0
+    }
 }
 
-trait KmpPrimitive: PartialEq {}
+#[cfg(feature = "simd")]
+impl KmpPrimitive for u8 {
+    fn first_candidate(&self, haystack: &[Self]) -> usize {
+        memchr::memchr(*self, haystack).unwrap_or(haystack.len())
+    }
+}
 
+#[cfg(not(feature = "simd"))]
 impl KmpPrimitive for u8 {}
+impl KmpPrimitive for i8 {}
+impl KmpPrimitive for u16 {}
+impl KmpPrimitive for i16 {}
+impl KmpPrimitive for u32 {}
+impl KmpPrimitive for i32 {}
+impl KmpPrimitive for u64 {}
+impl KmpPrimitive for i64 {}
+impl KmpPrimitive for u128 {}
+impl KmpPrimitive for i128 {}
+impl KmpPrimitive for usize {}
+impl KmpPrimitive for isize {}
 impl KmpPrimitive for char {}
 impl KmpPrimitive for bool {}
 
@@ -28,35 +168,130 @@ impl<T: KmpPrimitive> KmpMatchable<T> for T {
     fn match_haystack(&self, other: &T) -> bool {
         self == other
     }
+
+    fn first_candidate(&self, haystack: &[T]) -> usize {
+        KmpPrimitive::first_candidate(self, haystack)
+    }
+}
+
+/// `None` is a wildcard matching any item, like [`Wildcard::Any`].
+impl<T: KmpPrimitive> KmpSearchable for Option<T> {
+    fn is_match_guaranteed(&self, other: &Self) -> bool {
+        match (self, other) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some(a), Some(b)) => a == b,
+        }
+    }
+
+    fn is_match_possible(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        }
+    }
+}
+
+impl<T: KmpPrimitive> KmpMatchable<T> for Option<T> {
+    fn match_haystack(&self, other: &T) -> bool {
+        match self {
+            None => true,
+            Some(value) => value.match_haystack(other),
+        }
+    }
+}
+
+/// Unsigned integer type storing the offsets of a table.
+///
+/// Tables of short needles can use a narrower type than `usize` to take less memory.
+pub trait KmpIndex: Copy {
+    fn from_usize(value: usize) -> Option<Self>;
+
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! impl_kmp_index {
+    ($($index:ty),*) => {
+        $(
+            impl KmpIndex for $index {
+                fn from_usize(value: usize) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+
+                fn to_usize(self) -> usize {
+                    self as usize
+                }
+            }
+        )*
+    };
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct KmpTableItem {
-    needle: usize,
-    haystack: usize,
+impl_kmp_index!(u8, u16, u32, u64, usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KmpTableItem<I = usize> {
+    needle: I,
+    haystack: I,
+}
+
+impl<I: KmpIndex> KmpTableItem<I> {
+    /// Needle position to resume from after a mismatch: the length of the longest border
+    /// of the needle prefix ending at this item.
+    pub fn needle(&self) -> usize {
+        self.needle.to_usize()
+    }
+
+    /// Number of haystack items to read again after falling back, because the border is
+    /// only possibly matched. Always `0` for needles of primitive items.
+    pub fn haystack(&self) -> usize {
+        self.haystack.to_usize()
+    }
 }
 
-pub type KmpTable<'a> = &'a [KmpTableItem];
-pub type KmpOwnedTable = Vec<KmpTableItem>;
+pub type KmpTable<'a, I = usize> = &'a [KmpTableItem<I>];
+pub type KmpOwnedTable<I = usize> = Vec<KmpTableItem<I>>;
 
 fn kmp_table<N: KmpSearchable>(needle: &[N]) -> KmpOwnedTable {
+    kmp_table_by(needle, |item, other| {
+        item.is_match_possible(other)
+            .then(|| item.is_match_guaranteed(other))
+    })
+}
+
+/// Builds the table with `relate(item, other)` standing in for [`KmpSearchable`]: `None` if
+/// no haystack item can match both, otherwise whether every haystack item matching `item`
+/// also matches `other`.
+fn kmp_table_by<N, R>(needle: &[N], relate: R) -> KmpOwnedTable
+where
+    R: Fn(&N, &N) -> Option<bool>,
+{
+    let mut lsp = vec![KmpTableItem::default(); needle.len()];
+    kmp_table_into_by(needle, &mut lsp, relate);
+    lsp
+}
+
+/// Like [`kmp_table_by`], writing the table into `lsp`, which has one item per needle item.
+fn kmp_table_into_by<N, R>(needle: &[N], lsp: &mut [KmpTableItem], relate: R)
+where
+    R: Fn(&N, &N) -> Option<bool>,
+{
     if needle.is_empty() {
-        return vec![];
+        return;
     }
 
-    let mut lsp: KmpOwnedTable = Vec::with_capacity(needle.len());
-    lsp.push(KmpTableItem {
+    lsp[0] = KmpTableItem {
         needle: 0,
         haystack: 0,
-    });
+    };
 
-    for needle_item in &needle[1..] {
-        let mut item = *lsp.last().unwrap();
+    for (i, needle_item) in needle.iter().enumerate().skip(1) {
+        let mut item = lsp[i - 1];
 
         loop {
-            if needle_item.is_match_possible(&needle[item.needle]) {
+            if let Some(guaranteed) = relate(needle_item, &needle[item.needle]) {
                 if item.haystack == 0 {
-                    if !needle_item.is_match_guaranteed(&needle[item.needle]) {
+                    if !guaranteed {
                         item.haystack = 1;
                     }
                 } else {
@@ -71,18 +306,112 @@ fn kmp_table<N: KmpSearchable>(needle: &[N]) -> KmpOwnedTable {
                 break;
             }
 
+            // Possible matches are not transitive, so the borders of a border that is only
+            // possibly matched need not be borders of the needle prefix. Look for them
+            // directly instead of following the table.
+            if item.haystack != 0 {
+                item = (1..=item.needle)
+                    .rev()
+                    .find_map(|len| kmp_border(&needle[..=i], len, &relate))
+                    .unwrap_or(KmpTableItem {
+                        needle: 0,
+                        haystack: 0,
+                    });
+                break;
+            }
+
             item = lsp[item.needle - 1];
         }
 
-        lsp.push(item);
+        lsp[i] = item;
     }
+}
 
-    lsp
+/// Checks whether the last `len` items of `prefix` can match where its first `len` items
+/// did, returning the table item of that border.
+fn kmp_border<N, R>(prefix: &[N], len: usize, relate: R) -> Option<KmpTableItem>
+where
+    R: Fn(&N, &N) -> Option<bool>,
+{
+    let suffix = &prefix[prefix.len() - len..];
+    let mut haystack = 0;
+
+    for (suffix_item, prefix_item) in suffix.iter().zip(prefix) {
+        let guaranteed = relate(suffix_item, prefix_item)?;
+
+        if haystack != 0 || !guaranteed {
+            haystack += 1;
+        }
+    }
+
+    Some(KmpTableItem {
+        needle: len,
+        haystack,
+    })
+}
+
+fn is_valid_table<I: KmpIndex>(needle_len: usize, table: KmpTable<I>) -> bool {
+    table.len() == needle_len
+        && table
+            .iter()
+            .enumerate()
+            .all(|(i, item)| item.needle() <= i && item.haystack() <= item.needle())
+}
+
+/// Non-overlapping search with a previously computed table, like [`KmpPattern::find`]
+/// without building a pattern.
+///
+/// The table must be the one of `needle`, as returned by [`KmpPattern::table`]. Debug
+/// builds check that it has one item per needle item and offsets in range.
+pub fn kmp_find<'a, N, H, I>(
+    needle: &'a [N],
+    table: KmpTable<'a, I>,
+    haystack: &'a [H],
+) -> KmpSearch<'a, N, H, false, I>
+where
+    N: KmpMatchable<H>,
+    I: KmpIndex,
+{
+    debug_assert!(is_valid_table(needle.len(), table));
+    KmpSearch::new(needle, table, haystack)
 }
 
-pub struct KmpPattern<'a, N> {
+/// Overlapping counterpart of [`kmp_find`].
+pub fn kmp_find_overlapping<'a, N, H, I>(
     needle: &'a [N],
-    lsp: KmpOwnedTable,
+    table: KmpTable<'a, I>,
+    haystack: &'a [H],
+) -> KmpSearch<'a, N, H, true, I>
+where
+    N: KmpMatchable<H>,
+    I: KmpIndex,
+{
+    debug_assert!(is_valid_table(needle.len(), table));
+    KmpSearch::new(needle, table, haystack)
+}
+
+pub struct KmpPattern<'a, N, I: KmpIndex = usize> {
+    needle: &'a [N],
+    lsp: Cow<'a, [KmpTableItem<I>]>,
+}
+
+impl<'a, N: fmt::Debug, I: KmpIndex + fmt::Debug> fmt::Debug for KmpPattern<'a, N, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KmpPattern")
+            .field("needle", &self.needle)
+            .field("table", &self.lsp)
+            .finish()
+    }
+}
+
+// Cloning only copies the needle reference, so `N` need not be `Clone`.
+impl<'a, N, I: KmpIndex> Clone for KmpPattern<'a, N, I> {
+    fn clone(&self) -> Self {
+        Self {
+            needle: self.needle,
+            lsp: self.lsp.clone(),
+        }
+    }
 }
 
 impl<'a, N> KmpPattern<'a, N> {
@@ -90,69 +419,381 @@ impl<'a, N> KmpPattern<'a, N> {
     where
         N: KmpSearchable,
     {
+        // The tables of needles with at most one item do not depend on the items, so they
+        // are borrowed instead of being built and allocated for every such pattern.
+        if needle.len() <= 1 {
+            let trivial: KmpTable<'static> = &[KmpTableItem {
+                needle: 0,
+                haystack: 0,
+            }];
+
+            return Self {
+                needle,
+                lsp: Cow::Borrowed(&trivial[..needle.len()]),
+            };
+        }
+
         let table = kmp_table(needle);
 
-        Self { needle, lsp: table }
+        Self {
+            needle,
+            lsp: Cow::Owned(table),
+        }
+    }
+
+    /// Like [`KmpPattern::new`], building the table in the first `needle.len()` items of
+    /// `table_buf` instead of allocating it.
+    ///
+    /// Fails with [`KmpError::TableTooSmall`] if `table_buf` is shorter than the needle.
+    pub fn new_in(needle: &'a [N], table_buf: &'a mut [KmpTableItem]) -> Result<Self, KmpError>
+    where
+        N: KmpSearchable,
+    {
+        let lsp = table_buf
+            .get_mut(..needle.len())
+            .ok_or(KmpError::TableTooSmall {
+                needed: needle.len(),
+            })?;
+
+        kmp_table_into_by(needle, lsp, |item, other| {
+            item.is_match_possible(other)
+                .then(|| item.is_match_guaranteed(other))
+        });
+
+        Ok(Self {
+            needle,
+            lsp: Cow::Borrowed(lsp),
+        })
+    }
+
+    /// Like [`KmpPattern::new`], first checking that every needle item is consistent with
+    /// itself.
+    ///
+    /// Needles of primitive items are always accepted.
+    pub fn try_new(needle: &'a [N]) -> Result<Self, KmpError>
+    where
+        N: KmpSearchable,
+    {
+        check_needle(needle)?;
+        Ok(Self::new(needle))
+    }
+}
+
+/// Checks that every needle item is consistent with itself, see [`KmpPattern::try_new`].
+fn check_needle<N: KmpSearchable>(needle: &[N]) -> Result<(), KmpError> {
+    for (index, item) in needle.iter().enumerate() {
+        if !item.is_match_possible(item) {
+            return Err(KmpError::UnmatchableItem { index });
+        }
+
+        if !item.is_match_guaranteed(item) {
+            return Err(KmpError::InconsistentItem { index });
+        }
+    }
+
+    Ok(())
+}
+
+impl<'a, N, I: KmpIndex> KmpPattern<'a, N, I> {
+    /// Like [`KmpPattern::new`], but stores the table offsets as `I`.
+    ///
+    /// Returns `None` if the needle is too long for its offsets to fit in `I`.
+    pub fn new_indexed(needle: &'a [N]) -> Option<Self>
+    where
+        N: KmpSearchable,
+    {
+        I::from_usize(needle.len())?;
+
+        let table = kmp_table(needle)
+            .into_iter()
+            .map(|item| KmpTableItem {
+                needle: I::from_usize(item.needle).unwrap(),
+                haystack: I::from_usize(item.haystack).unwrap(),
+            })
+            .collect();
+
+        Some(Self {
+            needle,
+            lsp: Cow::Owned(table),
+        })
     }
 
-    pub fn table(&self) -> KmpTable {
+    /// Builds a pattern from a previously computed table without recomputing it.
+    ///
+    /// The table is trusted as is; use [`KmpPattern::validate_table`] to check tables coming
+    /// from untrusted sources.
+    pub fn from_parts(needle: &'a [N], table: KmpOwnedTable<I>) -> Self {
+        Self {
+            needle,
+            lsp: Cow::Owned(table),
+        }
+    }
+
+    /// Checks that the table has one item per needle item and that every offset stays in
+    /// range, so searching cannot index out of bounds.
+    pub fn validate_table(&self) -> bool {
+        is_valid_table(self.needle.len(), &self.lsp)
+    }
+
+    pub fn table(&self) -> KmpTable<'_, I> {
         &self.lsp
     }
 
-    pub fn find<H>(&'a self, haystack: &'a [H]) -> KmpSearch<'a, N, H, false>
+    /// Classic KMP failure function: the `needle` offset of each table item, that is the
+    /// length of the longest proper border of each needle prefix.
+    pub fn failure_function(&self) -> impl Iterator<Item = usize> + '_ {
+        self.lsp.iter().map(KmpTableItem::needle)
+    }
+
+    /// Non-overlapping search, yielding match starts in ascending order.
+    ///
+    /// The empty needle matches at every position from `0` to `haystack.len()` inclusive,
+    /// the end of the haystack included, like `str::match_indices("")`. Every search mode
+    /// and count agrees with this, so there are `haystack.len() + 1` empty matches.
+    pub fn find<H>(&'a self, haystack: &'a [H]) -> KmpSearch<'a, N, H, false, I>
     where
         N: KmpMatchable<H>,
     {
         KmpSearch::new(self.needle, &self.lsp, haystack)
     }
 
-    pub fn find_overlapping<H>(&'a self, haystack: &'a [H]) -> KmpSearch<'a, N, H, true>
+    /// Overlapping search, yielding the start of every occurrence in ascending order.
+    ///
+    /// The empty needle matches as in [`KmpPattern::find`].
+    pub fn find_overlapping<H>(&'a self, haystack: &'a [H]) -> KmpSearch<'a, N, H, true, I>
     where
         N: KmpMatchable<H>,
     {
         KmpSearch::new(self.needle, &self.lsp, haystack)
     }
+
+    /// Non-overlapping search starting at `start`, clamped to `haystack.len()`.
+    ///
+    /// Returned positions are absolute indices into `haystack`.
+    pub fn find_from<H>(&'a self, haystack: &'a [H], start: usize) -> KmpSearch<'a, N, H, false, I>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpSearch {
+            haystack_pos: start.min(haystack.len()),
+            ..KmpSearch::new(self.needle, &self.lsp, haystack)
+        }
+    }
+
+    /// Length of the longest proper needle prefix that is a suffix of `haystack`, i.e.
+    /// how many trailing items could still be the start of a match.
+    pub fn longest_prefix_at_end<H>(&self, haystack: &[H]) -> usize
+    where
+        N: KmpMatchable<H>,
+    {
+        let mut search = self.find_overlapping(haystack);
+        search.by_ref().for_each(drop);
+        search.matched_prefix_len()
+    }
+
+    /// Non-overlapping search restricted to matches lying entirely within `range`.
+    ///
+    /// Returned positions are absolute indices into `haystack`, and nothing past
+    /// `range.end` is read. The range is clamped to the haystack. As with [`find`] on a
+    /// slice, the empty needle also matches at `range.end`.
+    ///
+    /// [`find`]: KmpPattern::find
+    pub fn find_in<H>(
+        &'a self,
+        haystack: &'a [H],
+        range: Range<usize>,
+    ) -> KmpSearch<'a, N, H, false, I>
+    where
+        N: KmpMatchable<H>,
+    {
+        let end = range.end.min(haystack.len());
+        self.find_from(&haystack[..end], range.start)
+    }
+
+    /// Overlapping counterpart of [`KmpPattern::find_from`].
+    pub fn find_overlapping_from<H>(
+        &'a self,
+        haystack: &'a [H],
+        start: usize,
+    ) -> KmpSearch<'a, N, H, true, I>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpSearch {
+            haystack_pos: start.min(haystack.len()),
+            ..KmpSearch::new(self.needle, &self.lsp, haystack)
+        }
+    }
+
+    /// Overlapping counterpart of [`KmpPattern::find_in`].
+    pub fn find_overlapping_in<H>(
+        &'a self,
+        haystack: &'a [H],
+        range: Range<usize>,
+    ) -> KmpSearch<'a, N, H, true, I>
+    where
+        N: KmpMatchable<H>,
+    {
+        let end = range.end.min(haystack.len());
+        self.find_overlapping_from(&haystack[..end], range.start)
+    }
 }
 
-pub struct KmpSearch<'a, N, H, const OVERLAPPING: bool> {
+pub struct KmpSearch<'a, N, H, const OVERLAPPING: bool, I = usize> {
     needle: &'a [N],
-    lsp: &'a [KmpTableItem],
+    lsp: &'a [KmpTableItem<I>],
     haystack: &'a [H],
     needle_pos: usize,
     haystack_pos: usize,
+    // Last match returned from the front, and the state of the search from the back once
+    // `next_back` has been called.
+    front_last: Option<usize>,
+    back: Option<Box<KmpBack<'a, N, H>>>,
+    // Number of matches still allowed by `KmpSearch::limit`, from either end.
+    limit: usize,
+}
+
+impl<'a, N, H, const OVERLAPPING: bool, I> fmt::Debug for KmpSearch<'a, N, H, OVERLAPPING, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KmpSearch")
+            .field("needle_pos", &self.needle_pos)
+            .field("haystack_pos", &self.haystack_pos)
+            .field("overlapping", &OVERLAPPING)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, N, H, const OVERLAPPING: bool, I> Clone for KmpSearch<'a, N, H, OVERLAPPING, I> {
+    fn clone(&self) -> Self {
+        Self {
+            needle: self.needle,
+            lsp: self.lsp,
+            haystack: self.haystack,
+            needle_pos: self.needle_pos,
+            haystack_pos: self.haystack_pos,
+            front_last: self.front_last,
+            back: self.back.clone(),
+            limit: self.limit,
+        }
+    }
 }
 
-impl<'a, N, H, const OVERLAPPING: bool> KmpSearch<'a, N, H, OVERLAPPING> {
-    pub fn new(needle: &'a [N], lsp: &'a [KmpTableItem], haystack: &'a [H]) -> Self {
+impl<'a, N, H, const OVERLAPPING: bool, I> KmpSearch<'a, N, H, OVERLAPPING, I> {
+    /// Searches `haystack` with a table trusted to be the one of `needle`. A table that
+    /// does not fit the needle makes the search panic or return wrong matches, but never
+    /// causes undefined behavior; use [`KmpSearch::try_new`] for tables from elsewhere.
+    pub fn new(needle: &'a [N], lsp: &'a [KmpTableItem<I>], haystack: &'a [H]) -> Self {
         Self {
             needle,
             lsp,
             haystack,
             needle_pos: 0,
             haystack_pos: 0,
+            front_last: None,
+            back: None,
+            limit: usize::MAX,
+        }
+    }
+
+    /// Like [`KmpSearch::new`], first checking the table as
+    /// [`KmpPattern::validate_table`] does.
+    pub fn try_new(
+        needle: &'a [N],
+        lsp: &'a [KmpTableItem<I>],
+        haystack: &'a [H],
+    ) -> Result<Self, KmpError>
+    where
+        I: KmpIndex,
+    {
+        if !is_valid_table(needle.len(), lsp) {
+            return Err(KmpError::InvalidTable);
         }
+
+        Ok(Self::new(needle, lsp, haystack))
     }
 }
 
-impl<'a, N, H, const OVERLAPPING: bool> Iterator for KmpSearch<'a, N, H, OVERLAPPING>
-where
-    N: KmpMatchable<H>,
-{
-    type Item = usize;
+impl<'a, N, H, const OVERLAPPING: bool, I> KmpSearch<'a, N, H, OVERLAPPING, I> {
+    /// Length of the needle prefix matched by the most recently consumed haystack items.
+    ///
+    /// Once the search has returned `None` the whole haystack has been consumed, so this
+    /// is the length of the partial match pending at the end of the haystack.
+    pub fn matched_prefix_len(&self) -> usize {
+        self.needle_pos.min(self.needle.len())
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let needle_len = self.needle.len();
+    /// Automaton state as `(needle_pos, haystack_pos)`: the length of the pending partial
+    /// match and the number of haystack items consumed so far.
+    ///
+    /// After the search has returned `None`, `haystack_pos` is the haystack length and
+    /// `needle_pos` the length of the partial match at its end, which is the closest the
+    /// haystack came to completing a match there.
+    pub fn state(&self) -> (usize, usize) {
+        (self.needle_pos, self.haystack_pos)
+    }
 
-        if self.haystack_pos + needle_len - self.needle_pos > self.haystack.len() {
-            return None;
-        }
+    /// Stops the search after `n` more matches, counting those taken from either end.
+    ///
+    /// Unlike `take(n)`, the search stays a [`KmpSearch`], and once the limit is reached no
+    /// further haystack item is read. Matches taken from the back count towards the same
+    /// limit, so `limit(n).rev()` yields the last `n` matches rather than the first `n` in
+    /// reverse.
+    pub fn limit(self, n: usize) -> Self {
+        Self { limit: n, ..self }
+    }
+
+    /// Restarts the search over another haystack, keeping the needle and the table.
+    pub fn reset<'b>(self, haystack: &'b [H]) -> KmpSearch<'b, N, H, OVERLAPPING, I>
+    where
+        'a: 'b,
+    {
+        KmpSearch::new(self.needle, self.lsp, haystack)
+    }
+}
+
+impl<'a, N, H, const OVERLAPPING: bool, I: KmpIndex> KmpSearch<'a, N, H, OVERLAPPING, I> {
+    /// Advances to the next match, comparing needle and haystack items with `matches`.
+    pub(crate) fn next_by<M>(&mut self, matches: M) -> Option<usize>
+    where
+        M: FnMut(&N, &H) -> bool,
+    {
+        self.next_by_skipping(matches, |_, _| 0)
+    }
+
+    /// Like [`KmpSearch::next_by`], jumping ahead by `skip(&needle[0], rest)` items whenever
+    /// no partial match is pending, as with [`KmpMatchable::first_candidate`].
+    pub(crate) fn next_by_skipping<M, S>(&mut self, mut matches: M, mut skip: S) -> Option<usize>
+    where
+        M: FnMut(&N, &H) -> bool,
+        S: FnMut(&N, &[H]) -> usize,
+    {
+        let needle_len = self.needle.len();
 
+        // The empty needle matches at every position up to and including the haystack
+        // length, after which `haystack_pos` is left one past it. For a haystack of
+        // `usize::MAX` zero-sized items that is not representable, so `needle_pos`, which
+        // is otherwise always 0 here, marks the search as finished instead.
         if needle_len == 0 {
-            self.haystack_pos += 1;
-            return Some(self.haystack_pos - 1);
+            if self.haystack_pos > self.haystack.len() || self.needle_pos != 0 {
+                return None;
+            }
+
+            let pos = self.haystack_pos;
+            match pos.checked_add(1) {
+                Some(next) => self.haystack_pos = next,
+                None => self.needle_pos = 1,
+            }
+            return Some(pos);
         }
 
+        // The haystack is consumed to the end even once no further match can fit, so that
+        // the automaton state accounts for every item after `None` is returned. Streaming
+        // searches rely on this to carry partial matches across buffer boundaries.
         loop {
+            if self.needle_pos == 0 && self.haystack_pos < self.haystack.len() {
+                self.haystack_pos += skip(&self.needle[0], &self.haystack[self.haystack_pos..]);
+            }
+
             if self.haystack_pos >= self.haystack.len() {
                 return None;
             }
@@ -161,7 +802,7 @@ where
             self.haystack_pos += 1;
 
             loop {
-                if self.needle[self.needle_pos].match_haystack(haystack_item) {
+                if matches(&self.needle[self.needle_pos], haystack_item) {
                     self.needle_pos += 1;
 
                     if self.needle_pos != needle_len {
@@ -172,10 +813,10 @@ where
 
                     if OVERLAPPING {
                         let back = self.lsp[self.needle_pos - 1];
-                        self.needle_pos = back.needle;
-                        if back.haystack != 0 {
-                            self.needle_pos -= back.haystack;
-                            self.haystack_pos -= back.haystack;
+                        self.needle_pos = back.needle();
+                        if back.haystack() != 0 {
+                            self.needle_pos -= back.haystack();
+                            self.haystack_pos -= back.haystack();
                         }
                     } else {
                         self.needle_pos = 0;
@@ -189,17 +830,124 @@ where
                 }
 
                 let back = &self.lsp[self.needle_pos - 1];
-                self.needle_pos = back.needle;
-                if back.haystack != 0 {
-                    self.needle_pos -= back.haystack;
-                    self.haystack_pos -= back.haystack;
-                    haystack_item = &self.haystack[self.haystack_pos];
+                self.needle_pos = back.needle();
+                if back.haystack() != 0 {
+                    self.needle_pos -= back.haystack();
+                    self.haystack_pos -= back.haystack();
+                    haystack_item = &self.haystack[self.haystack_pos - 1];
                 }
             }
         }
     }
 }
 
+impl<'a, N, H, const OVERLAPPING: bool, I: KmpIndex> Iterator
+    for KmpSearch<'a, N, H, OVERLAPPING, I>
+where
+    N: KmpMatchable<H>,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.limit == 0 {
+            return None;
+        }
+
+        let pos = self.next_by_skipping(item_matches, N::first_candidate)?;
+
+        if self.back.as_ref().is_some_and(|back| back.yielded(pos)) {
+            return None;
+        }
+
+        self.front_last = Some(pos);
+        self.limit -= 1;
+        Some(pos)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.unlimited_size_hint();
+        let upper = match upper {
+            Some(upper) => Some(upper.min(self.limit)),
+            None => (self.limit != usize::MAX).then_some(self.limit),
+        };
+
+        (lower.min(self.limit), upper)
+    }
+}
+
+impl<'a, N, H, const OVERLAPPING: bool, I> KmpSearch<'a, N, H, OVERLAPPING, I> {
+    /// Bounds on the number of matches left, ignoring [`KmpSearch::limit`].
+    fn unlimited_size_hint(&self) -> (usize, Option<usize>) {
+        let needle_len = self.needle.len();
+        let haystack_len = self.haystack.len();
+
+        // The empty needle matches at every position, so the count is exact: those left
+        // between the front and the lowest position yielded from the back.
+        // The count does not fit in `usize` for a fresh search over `usize::MAX` zero-sized
+        // items.
+        if needle_len == 0 {
+            let remaining = if self.needle_pos != 0 {
+                Some(0)
+            } else if let Some(end) = self.back.as_ref().and_then(|back| back.last()) {
+                Some(end.saturating_sub(self.haystack_pos))
+            } else {
+                haystack_len
+                    .checked_sub(self.haystack_pos)
+                    .map_or(Some(0), |left| left.checked_add(1))
+            };
+            return (remaining.unwrap_or(usize::MAX), remaining);
+        }
+
+        // Matches can only start at or after the current alignment. The subtraction only
+        // saturates for a state resumed from another haystack.
+        let start = self.haystack_pos.saturating_sub(self.needle_pos);
+        let available = haystack_len.saturating_sub(start);
+
+        let upper = if OVERLAPPING {
+            (available + 1).saturating_sub(needle_len)
+        } else {
+            available / needle_len
+        };
+
+        (0, Some(upper))
+    }
+}
+
+impl<'a, N, H, const OVERLAPPING: bool, I: KmpIndex> FusedIterator
+    for KmpSearch<'a, N, H, OVERLAPPING, I>
+where
+    N: KmpMatchable<H>,
+{
+}
+
+#[cfg(test)]
+pub(crate) mod test_util {
+    use alloc::vec::Vec;
+
+    use crate::Wildcard;
+
+    /// Xorshift generator returning numbers below the given bound.
+    pub(crate) fn xorshift(mut seed: u32) -> impl FnMut(usize) -> usize {
+        move |bound| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as usize % bound
+        }
+    }
+
+    /// Needle of exact bytes, with `?` matching any byte.
+    pub(crate) fn wildcard_needle(pattern: &[u8]) -> Vec<Wildcard<u8>> {
+        pattern
+            .iter()
+            .map(|&b| match b {
+                b'?' => Wildcard::Any,
+                b => Wildcard::Exact(b),
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{KmpMatchable, KmpPattern, KmpSearchable};
@@ -238,9 +986,7 @@ mod tests {
     where
         N: KmpSearchable + KmpMatchable<H>,
     {
-        let pattern = KmpPattern::new(needle);
-        let found_matches: Vec<_> = pattern.find_overlapping(&haystack).collect();
-        found_matches
+        KmpPattern::new(needle).all_matches_overlapping(haystack)
     }
 
     // Tests taken from https://gitlab.com/bit-refined/kmp/
@@ -251,9 +997,7 @@ mod tests {
         where
             N: KmpSearchable + KmpMatchable<H>,
         {
-            let pattern = KmpPattern::new(needle);
-            let found_matches: Vec<_> = pattern.find(&haystack).collect();
-            found_matches
+            KmpPattern::new(needle).all_matches(haystack)
         }
 
         #[test]
@@ -307,6 +1051,13 @@ mod tests {
         fn needle_longer_haystack() {
             assert!(kmp_match(&['a', 'b', 'c'], &['a', 'b']).is_empty());
         }
+
+        #[test]
+        fn integers() {
+            assert_eq!(vec![1, 4], kmp_match(&[7u32, 8], &[1, 7, 8, 9, 7, 8, 7]));
+            assert_eq!(vec![0], kmp_match(&[-1i32, 0], &[-1, 0, 1]));
+            assert_eq!(vec![1], kmp_match(&[u128::MAX], &[0, u128::MAX]));
+        }
     }
 
     mod find {
@@ -317,7 +1068,7 @@ mod tests {
             N: KmpSearchable + KmpMatchable<H>,
         {
             let pattern = KmpPattern::new(needle);
-            pattern.find(&haystack).next()
+            pattern.find(haystack).next()
         }
 
         #[test]
@@ -355,16 +1106,929 @@ mod tests {
         }
     }
 
-    mod table {
-        use crate::{kmp_table as kd, KmpSearchable};
+    mod empty_needle {
+        use crate::KmpPattern;
 
-        fn kmp_table<T: KmpSearchable>(needle: &[T]) -> Vec<usize> {
-            kd(needle).iter().map(|x| x.needle).collect()
+        #[test]
+        fn matches_str() {
+            let pattern = KmpPattern::<u8>::new(b"");
+
+            for haystack in ["", "a", "ab", "abcdef"] {
+                let expected: Vec<_> = haystack.match_indices("").map(|(pos, _)| pos).collect();
+                let haystack = haystack.as_bytes();
+
+                assert_eq!(pattern.find(haystack).collect::<Vec<_>>(), expected);
+                assert_eq!(
+                    pattern.find_overlapping(haystack).collect::<Vec<_>>(),
+                    expected
+                );
+                assert_eq!(pattern.count_matches(haystack), expected.len());
+                assert_eq!(pattern.count_matches_overlapping(haystack), expected.len());
+                assert_eq!(pattern.find(haystack).next_back(), Some(haystack.len()));
+                assert_eq!(pattern.rfind(haystack), Some(haystack.len()));
+            }
         }
+    }
+
+    mod try_new {
+        use crate::{CharClass, KmpError, KmpPattern, KmpSearchable};
 
         #[test]
-        fn basic() {
-            assert_eq!(kmp_table(&['a', 'a', 'a', 'b']), vec![0, 1, 2, 0]);
+        fn accepts_primitives() {
+            let pattern = KmpPattern::try_new(b"abacabab").unwrap();
+            assert_eq!(pattern.table(), KmpPattern::new(b"abacabab").table());
+            assert!(KmpPattern::<u8>::try_new(b"").is_ok());
+        }
+
+        #[test]
+        fn rejects_unmatchable() {
+            let needle = [CharClass::single('a'), CharClass::new(None)];
+            assert_eq!(
+                KmpPattern::try_new(&needle).unwrap_err(),
+                KmpError::UnmatchableItem { index: 1 }
+            );
+        }
+
+        #[test]
+        fn rejects_inconsistent() {
+            #[derive(Debug)]
+            struct Never;
+
+            impl KmpSearchable for Never {
+                fn is_match_possible(&self, _other: &Self) -> bool {
+                    true
+                }
+
+                fn is_match_guaranteed(&self, _other: &Self) -> bool {
+                    false
+                }
+            }
+
+            assert_eq!(
+                KmpPattern::try_new(&[Never]).unwrap_err(),
+                KmpError::InconsistentItem { index: 0 }
+            );
+        }
+    }
+
+    mod table_item {
+        use crate::{KmpPattern, Wildcard};
+
+        #[test]
+        fn accessors() {
+            let pattern = KmpPattern::new(b"abacabab");
+            let table = pattern.table();
+
+            assert_eq!(
+                table.iter().map(|item| item.needle()).collect::<Vec<_>>(),
+                [0, 0, 1, 0, 1, 2, 3, 2]
+            );
+            assert!(table.iter().all(|item| item.haystack() == 0));
+        }
+
+        #[test]
+        fn uncertain_border() {
+            let needle = [Wildcard::Exact(b'a'), Wildcard::Any];
+            let pattern = KmpPattern::new(&needle);
+            let item = pattern.table()[1];
+
+            assert_eq!((item.needle(), item.haystack()), (1, 1));
+        }
+    }
+
+    mod trivial_table {
+        use crate::{kmp_table, CharClass, KmpPattern, NotEqual, Wildcard};
+
+        #[test]
+        fn matches_general_path() {
+            assert_eq!(KmpPattern::new(b"a").table(), kmp_table(b"a"));
+            assert_eq!(KmpPattern::<u8>::new(b"").table(), kmp_table::<u8>(b""));
+            assert_eq!(
+                KmpPattern::new(&[Wildcard::<u8>::Any]).table(),
+                kmp_table(&[Wildcard::<u8>::Any])
+            );
+            assert_eq!(
+                KmpPattern::new(&[NotEqual(1)]).table(),
+                kmp_table(&[NotEqual(1)])
+            );
+            assert_eq!(
+                KmpPattern::new(&[CharClass::new(None)]).table(),
+                kmp_table(&[CharClass::new(None)])
+            );
+        }
+
+        #[test]
+        fn single_item_search() {
+            let pattern = KmpPattern::new(b"a");
+            assert_eq!(pattern.all_matches(b"abaa"), [0, 2, 3]);
+            assert_eq!(pattern.all_matches_overlapping(b"abaa"), [0, 2, 3]);
+            assert_eq!(pattern.count_matches_overlapping(b"abaa"), 3);
+            assert_eq!(pattern.find(b"abaa").rev().collect::<Vec<_>>(), [3, 2, 0]);
+        }
+    }
+
+    mod failure_function {
+        use crate::{KmpPattern, Wildcard};
+
+        #[test]
+        fn classic() {
+            let pattern = KmpPattern::new(b"abacabab");
+            assert_eq!(
+                pattern.failure_function().collect::<Vec<_>>(),
+                [0, 0, 1, 0, 1, 2, 3, 2]
+            );
+
+            let pattern = KmpPattern::<u8, u8>::new_indexed(b"aaaa").unwrap();
+            assert_eq!(pattern.failure_function().collect::<Vec<_>>(), [0, 1, 2, 3]);
+        }
+
+        #[test]
+        fn ignores_haystack_offsets() {
+            let needle = [Wildcard::Exact(b'a'), Wildcard::Any];
+            let pattern = KmpPattern::new(&needle);
+
+            assert_eq!(
+                pattern.failure_function().collect::<Vec<_>>(),
+                pattern
+                    .table()
+                    .iter()
+                    .map(|item| item.needle)
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    mod quick_reject {
+        use core::cell::Cell;
+
+        use crate::{KmpMatchable, KmpPattern, KmpSearchable};
+
+        #[derive(Debug, Clone, PartialEq)]
+        enum Value {
+            Int(i64),
+            Text(String),
+        }
+
+        /// Needle value counting full comparisons, optionally rejecting other variants early.
+        struct Probe<'c> {
+            value: Value,
+            quick: bool,
+            calls: &'c Cell<usize>,
+        }
+
+        impl KmpSearchable for Probe<'_> {
+            fn is_match_possible(&self, other: &Self) -> bool {
+                self.value == other.value
+            }
+
+            fn is_match_guaranteed(&self, other: &Self) -> bool {
+                self.value == other.value
+            }
+        }
+
+        impl KmpMatchable<Value> for Probe<'_> {
+            fn match_haystack(&self, other: &Value) -> bool {
+                self.calls.set(self.calls.get() + 1);
+                self.value == *other
+            }
+
+            fn quick_reject(&self, other: &Value) -> bool {
+                self.quick && core::mem::discriminant(&self.value) != core::mem::discriminant(other)
+            }
+        }
+
+        #[test]
+        fn same_results_fewer_comparisons() {
+            let values = [
+                Value::Int(1),
+                Value::Text("a".into()),
+                Value::Int(2),
+                Value::Int(1),
+                Value::Text("a".into()),
+                Value::Text("b".into()),
+                Value::Int(1),
+                Value::Text("a".into()),
+            ];
+            let needle = [Value::Int(1), Value::Text("a".into())];
+
+            let run = |quick: bool| {
+                let calls = Cell::new(0);
+                let probes: Vec<_> = needle
+                    .iter()
+                    .map(|value| Probe {
+                        value: value.clone(),
+                        quick,
+                        calls: &calls,
+                    })
+                    .collect();
+                let pattern = KmpPattern::new(&probes);
+
+                let found = (
+                    pattern.all_matches(&values),
+                    pattern.all_matches_overlapping(&values),
+                    pattern.count_matches_overlapping(&values),
+                    pattern.matches_at(&values, 3),
+                );
+                (found, calls.get())
+            };
+
+            let (slow, slow_calls) = run(false);
+            let (fast, fast_calls) = run(true);
+
+            assert_eq!(slow, (vec![0, 3, 6], vec![0, 3, 6], 3, true));
+            assert_eq!(fast, slow);
+            assert!(fast_calls < slow_calls);
+        }
+    }
+
+    mod cross_type {
+        use crate::{KmpMatchable, KmpPattern, KmpSearchable};
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        enum Token {
+            Digit,
+            Alpha,
+            Byte(u8),
+        }
+
+        impl KmpSearchable for Token {
+            fn is_match_possible(&self, other: &Self) -> bool {
+                match (self, other) {
+                    (Token::Byte(a), b) | (b, Token::Byte(a)) => b.match_haystack(a),
+                    (a, b) => a == b,
+                }
+            }
+
+            fn is_match_guaranteed(&self, other: &Self) -> bool {
+                match (self, other) {
+                    (Token::Byte(a), b) => b.match_haystack(a),
+                    (a, b) => a == b,
+                }
+            }
+        }
+
+        impl KmpMatchable<u8> for Token {
+            fn match_haystack(&self, other: &u8) -> bool {
+                match self {
+                    Token::Digit => other.is_ascii_digit(),
+                    Token::Alpha => other.is_ascii_alphabetic(),
+                    Token::Byte(byte) => byte == other,
+                }
+            }
+        }
+
+        #[test]
+        fn tokens_in_bytes() {
+            let needle = [Token::Alpha, Token::Digit, Token::Byte(b'-'), Token::Digit];
+            let pattern = KmpPattern::new(&needle);
+
+            assert_eq!(pattern.all_matches(b"a1-2 b-3 c4-5x"), [0, 9]);
+            assert!(pattern.all_matches(b"11-2").is_empty());
+        }
+
+        #[test]
+        fn overlapping_classes() {
+            let needle = [Token::Byte(b'a'), Token::Alpha, Token::Byte(b'a')];
+            let pattern = KmpPattern::new(&needle);
+
+            assert_eq!(pattern.all_matches_overlapping(b"aaaaba"), [0, 1, 3]);
+        }
+    }
+
+    mod option {
+        use crate::test_util::xorshift;
+        use crate::{KmpPattern, Wildcard};
+
+        #[test]
+        fn none_matches_anything() {
+            let needle = [Some(b'a'), None, Some(b'c')];
+            let pattern = KmpPattern::new(&needle);
+
+            assert_eq!(pattern.all_matches(b"aXc"), [0]);
+            assert!(pattern.all_matches(b"ab").is_empty());
+            assert_eq!(pattern.all_matches_overlapping(b"aacacc"), [0, 3]);
+        }
+
+        #[test]
+        fn matches_wildcard() {
+            let mut next = xorshift(0x85eb_ca6b);
+
+            for _ in 0..500 {
+                let needle: Vec<_> = (0..1 + next(6))
+                    .map(|_| [None, Some(b'a'), Some(b'b')][next(3)])
+                    .collect();
+                let wildcards: Vec<_> = needle
+                    .iter()
+                    .map(|item| item.map_or(Wildcard::Any, Wildcard::Exact))
+                    .collect();
+                let haystack: Vec<u8> = (0..next(24)).map(|_| b"ab"[next(2)]).collect();
+
+                let pattern = KmpPattern::new(&needle);
+                let expected = KmpPattern::new(&wildcards);
+
+                assert_eq!(pattern.table(), expected.table(), "needle: {:?}", needle);
+                assert_eq!(
+                    pattern.all_matches_overlapping(&haystack),
+                    expected.all_matches_overlapping(&haystack),
+                    "needle: {:?}, haystack: {:?}",
+                    needle,
+                    haystack
+                );
+            }
+        }
+    }
+
+    mod new_in {
+        use crate::{KmpError, KmpPattern, KmpTableItem, Wildcard};
+
+        #[test]
+        fn fills_buffer() {
+            let mut buf = [KmpTableItem::default(); 16];
+            let pattern = KmpPattern::new_in(b"abacabab", &mut buf).unwrap();
+
+            assert_eq!(pattern.table(), KmpPattern::new(b"abacabab").table());
+            assert_eq!(pattern.find(b"abacababacabab").collect::<Vec<_>>(), [0]);
+            assert_eq!(
+                pattern
+                    .find_overlapping(b"abacababacabab")
+                    .collect::<Vec<_>>(),
+                [0, 6]
+            );
+
+            let needle = [Wildcard::Exact(b'a'), Wildcard::Any, Wildcard::Exact(b'a')];
+            let mut buf = [KmpTableItem::default(); 3];
+            let pattern = KmpPattern::new_in(&needle, &mut buf).unwrap();
+            assert_eq!(pattern.table(), KmpPattern::new(&needle).table());
+
+            assert!(KmpPattern::<u8>::new_in(b"", &mut []).is_ok());
+        }
+
+        #[test]
+        fn too_small() {
+            let mut buf = [KmpTableItem::default(); 3];
+            assert_eq!(
+                KmpPattern::new_in(b"abcd", &mut buf).unwrap_err(),
+                KmpError::TableTooSmall { needed: 4 }
+            );
+        }
+    }
+
+    mod limit {
+        use crate::KmpPattern;
+
+        #[test]
+        fn stops_after_limit() {
+            let pattern = KmpPattern::new(b"a");
+            let mut search = pattern.find(b"aaaa").limit(2);
+
+            assert_eq!(search.size_hint(), (0, Some(2)));
+            assert_eq!(search.next(), Some(0));
+            assert_eq!(search.next(), Some(1));
+            assert_eq!(search.next(), None);
+            assert_eq!(search.state(), (0, 2));
+
+            assert!(pattern.find(b"aaaa").limit(0).next().is_none());
+            assert_eq!(pattern.find_overlapping(b"aaaa").limit(3).count(), 3);
+        }
+
+        #[test]
+        fn both_ends() {
+            let pattern = KmpPattern::new(b"a");
+            let mut search = pattern.find(b"aaaa").limit(3);
+
+            assert_eq!(search.next_back(), Some(3));
+            assert_eq!(search.next(), Some(0));
+            assert_eq!(search.next_back(), Some(2));
+            assert_eq!(search.next(), None);
+            assert_eq!(search.next_back(), None);
+
+            assert_eq!(
+                pattern
+                    .find_overlapping(b"aaaa")
+                    .limit(2)
+                    .rev()
+                    .collect::<Vec<_>>(),
+                [3, 2]
+            );
+        }
+
+        #[test]
+        fn empty_needle() {
+            let pattern = KmpPattern::<u8>::new(b"");
+            let search = pattern.find(b"abc").limit(2);
+
+            assert_eq!(search.size_hint(), (2, Some(2)));
+            assert_eq!(search.collect::<Vec<_>>(), [0, 1]);
+            assert_eq!(pattern.find(b"abc").limit(10).size_hint(), (4, Some(4)));
+        }
+    }
+
+    mod search_try_new {
+        use crate::{kmp_table, KmpError, KmpSearch, KmpTableItem};
+
+        #[test]
+        fn checks_table() {
+            let table = kmp_table(b"abab");
+            let search = KmpSearch::<_, _, true>::try_new(b"abab", &table, b"ababab").unwrap();
+            assert_eq!(search.collect::<Vec<_>>(), [0, 2]);
+
+            for needle in [&b"aba"[..], b"ababa"] {
+                assert_eq!(
+                    KmpSearch::<_, _, false>::try_new(needle, &table, b"abab").unwrap_err(),
+                    KmpError::InvalidTable
+                );
+            }
+
+            let corrupted = [
+                KmpTableItem {
+                    needle: 1,
+                    haystack: 0,
+                },
+                KmpTableItem::default(),
+            ];
+            assert!(KmpSearch::<_, _, false>::try_new(b"ab", &corrupted, b"ab").is_err());
+        }
+    }
+
+    mod free_functions {
+        use crate::{kmp_find, kmp_find_overlapping, kmp_table, KmpPattern};
+
+        #[test]
+        fn matches_pattern() {
+            let needle = b"abab";
+            let haystack = b"abababxabab";
+            let table = kmp_table(needle);
+            let pattern = KmpPattern::new(needle);
+
+            assert_eq!(
+                kmp_find(needle, &table, haystack).collect::<Vec<_>>(),
+                pattern.all_matches(haystack)
+            );
+            assert_eq!(
+                kmp_find_overlapping(needle, &table, haystack).collect::<Vec<_>>(),
+                pattern.all_matches_overlapping(haystack)
+            );
+        }
+
+        #[test]
+        #[cfg(debug_assertions)]
+        #[should_panic]
+        fn rejects_mismatched_table() {
+            let table = kmp_table(b"ab");
+            kmp_find(b"abc", &table, b"abc");
+        }
+    }
+
+    mod from_parts {
+        use crate::{kmp_table, KmpPattern, KmpTableItem};
+
+        #[test]
+        fn reuse_table() {
+            let table = kmp_table(b"abab");
+            let pattern = KmpPattern::from_parts(b"abab", table);
+
+            assert!(pattern.validate_table());
+            assert_eq!(
+                pattern.find_overlapping(b"abababab").collect::<Vec<_>>(),
+                [0, 2, 4]
+            );
+        }
+
+        #[test]
+        fn empty_needle() {
+            let pattern = KmpPattern::<u8>::from_parts(b"", vec![]);
+            assert!(pattern.validate_table());
+            assert_eq!(pattern.find(b"ab").collect::<Vec<_>>(), [0, 1, 2]);
+        }
+
+        #[test]
+        fn invalid_tables() {
+            let item = |needle: usize, haystack: usize| KmpTableItem { needle, haystack };
+
+            assert!(!KmpPattern::from_parts(b"ab", vec![item(0, 0)]).validate_table());
+            assert!(!KmpPattern::from_parts(b"a", vec![item(1, 0)]).validate_table());
+            assert!(!KmpPattern::from_parts(b"ab", vec![item(0, 0), item(1, 2)]).validate_table());
+            assert!(KmpPattern::from_parts(b"ab", vec![item(0, 0), item(1, 1)]).validate_table());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use crate::{kmp_table, KmpOwnedTable, KmpPattern};
+
+        #[test]
+        fn table_round_trip() {
+            let needle = b"abacabab";
+            let haystack = b"abacababacabababacabab";
+
+            let serialized = serde_json::to_string(&kmp_table(needle)).unwrap();
+            let table: KmpOwnedTable = serde_json::from_str(&serialized).unwrap();
+            let pattern = KmpPattern::from_parts(needle, table);
+            let fresh = KmpPattern::new(needle);
+
+            assert!(pattern.validate_table());
+            assert_eq!(pattern.table(), fresh.table());
+            assert_eq!(
+                pattern.find_overlapping(haystack).collect::<Vec<_>>(),
+                fresh.find_overlapping(haystack).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[cfg(feature = "simd")]
+    mod simd {
+        use crate::test_util::xorshift;
+        use crate::{KmpPattern, Wildcard};
+
+        #[test]
+        fn matches_scalar() {
+            let mut next = xorshift(0x9e37_79b9);
+
+            // A large alphabet keeps the first needle byte rare, so long stretches are skipped.
+            let haystack: Vec<u8> = (0..1 << 16).map(|_| next(64) as u8).collect();
+
+            for _ in 0..200 {
+                let start = next(haystack.len() - 8);
+                let needle = &haystack[start..start + 1 + next(6)];
+                let exact: Vec<_> = needle.iter().copied().map(Wildcard::Exact).collect();
+
+                let fast = KmpPattern::new(needle);
+                let scalar = KmpPattern::new(&exact);
+
+                assert_eq!(
+                    fast.find(&haystack).collect::<Vec<_>>(),
+                    scalar.find(&haystack).collect::<Vec<_>>(),
+                    "needle: {:?}",
+                    needle
+                );
+                assert_eq!(
+                    fast.find_overlapping(&haystack).collect::<Vec<_>>(),
+                    scalar.find_overlapping(&haystack).collect::<Vec<_>>(),
+                    "needle: {:?}",
+                    needle
+                );
+            }
+        }
+    }
+
+    mod fused {
+        use crate::KmpPattern;
+
+        fn assert_fused<I: Iterator<Item = usize>>(mut search: I, expected: usize) {
+            assert_eq!(search.by_ref().count(), expected);
+            for _ in 0..5 {
+                assert_eq!(search.next(), None);
+            }
+        }
+
+        #[test]
+        fn fused() {
+            let pattern = KmpPattern::new(b"aa");
+            assert_fused(pattern.find(b"aaaaa"), 2);
+            assert_fused(pattern.find_overlapping(b"aaaaa"), 4);
+            assert_fused(pattern.find(b"xa"), 0);
+        }
+
+        #[test]
+        fn fused_empty_needle() {
+            let pattern = KmpPattern::<u8>::new(b"");
+            assert_fused(pattern.find(b"abc"), 4);
+            assert_fused(pattern.find_overlapping(b"abc"), 4);
+        }
+    }
+
+    mod size_hint {
+        use crate::KmpPattern;
+
+        fn assert_upper_bound<I: Iterator<Item = usize>>(mut search: I, total: usize) {
+            for remaining in (0..=total).rev() {
+                let (lower, upper) = search.size_hint();
+                assert!(lower <= remaining);
+                assert!(upper.unwrap() >= remaining);
+                search.next();
+            }
+        }
+
+        #[test]
+        fn upper_bound() {
+            const TEST_CASES: &[(&[u8], &[u8])] = &[
+                (b"aa", b"aaaaa"),
+                (b"a", b"aaaa"),
+                (b"aba", b"abababa"),
+                (b"abc", b"xabcabcx"),
+                (b"abcdef", b"abc"),
+                (b"", b"abc"),
+            ];
+
+            for (needle, haystack) in TEST_CASES {
+                let pattern = KmpPattern::new(needle);
+                let total = pattern.find(haystack).count();
+                assert_upper_bound(pattern.find(haystack), total);
+                let total = pattern.find_overlapping(haystack).count();
+                assert_upper_bound(pattern.find_overlapping(haystack), total);
+            }
+        }
+
+        #[test]
+        fn exact_for_empty_needle() {
+            let pattern = KmpPattern::<u8>::new(b"");
+
+            for n in 0..4 {
+                let haystack = vec![0; n];
+                assert_eq!(pattern.find(&haystack).size_hint(), (n + 1, Some(n + 1)));
+                assert_eq!(
+                    pattern.find_overlapping(&haystack).size_hint(),
+                    (n + 1, Some(n + 1))
+                );
+
+                let matches: Vec<_> = pattern.find(&haystack).collect();
+                assert_eq!(matches.len(), n + 1);
+            }
+
+            let mut search = pattern.find(b"abcd");
+            search.next();
+            search.next_back();
+            assert_eq!(search.size_hint(), (3, Some(3)));
+            assert_eq!(search.by_ref().count(), 3);
+            assert_eq!(search.size_hint(), (0, Some(0)));
+        }
+    }
+
+    mod find_from {
+        use crate::KmpPattern;
+
+        #[test]
+        fn skip_first_match() {
+            let pattern = KmpPattern::new(b"abc");
+            assert_eq!(pattern.find_from(b"abcabc", 1).collect::<Vec<_>>(), [3]);
+            assert_eq!(pattern.find_from(b"abcabc", 3).collect::<Vec<_>>(), [3]);
+            assert_eq!(pattern.find_from(b"abcabc", 0).collect::<Vec<_>>(), [0, 3]);
+        }
+
+        #[test]
+        fn clamp_start() {
+            let pattern = KmpPattern::new(b"abc");
+            assert!(pattern.find_from(b"abcabc", 10).next().is_none());
+
+            let empty = KmpPattern::<u8>::new(b"");
+            assert_eq!(empty.find_from(b"abc", 10).collect::<Vec<_>>(), [3]);
+            assert_eq!(empty.find_from(b"abc", 1).collect::<Vec<_>>(), [1, 2, 3]);
+        }
+    }
+
+    mod find_in {
+        use crate::KmpPattern;
+
+        #[test]
+        fn window() {
+            let pattern = KmpPattern::new(b"ab");
+            let haystack = b"abxabxabxab";
+            assert_eq!(pattern.find_in(haystack, 1..9).collect::<Vec<_>>(), [3, 6]);
+            assert_eq!(
+                pattern.find_in(haystack, 0..100).collect::<Vec<_>>(),
+                [0, 3, 6, 9]
+            );
+        }
+
+        #[test]
+        fn straddling_end() {
+            let pattern = KmpPattern::new(b"ab");
+            assert!(pattern.find_in(b"xxab", 0..3).next().is_none());
+            assert!(pattern.find_in(b"xxab", 3..4).next().is_none());
+        }
+
+        #[test]
+        fn empty_range() {
+            let pattern = KmpPattern::new(b"ab");
+            assert!(pattern.find_in(b"abab", 2..2).next().is_none());
+            #[allow(clippy::reversed_empty_ranges)]
+            let reversed = 3..1;
+            assert!(pattern.find_in(b"abab", reversed).next().is_none());
+
+            let empty = KmpPattern::<u8>::new(b"");
+            assert_eq!(empty.find_in(b"abab", 2..2).collect::<Vec<_>>(), [2]);
+            assert_eq!(empty.find_in(b"abab", 1..3).collect::<Vec<_>>(), [1, 2, 3]);
+        }
+
+        #[test]
+        fn needle_longer_than_window() {
+            let pattern = KmpPattern::new(b"abab");
+            assert!(pattern.find_in(b"ababab", 1..4).next().is_none());
+        }
+
+        #[test]
+        fn overlapping() {
+            let pattern = KmpPattern::new(b"aa");
+            let haystack = b"aaaaxaa";
+            assert_eq!(
+                pattern
+                    .find_overlapping_from(haystack, 1)
+                    .collect::<Vec<_>>(),
+                [1, 2, 5]
+            );
+            assert_eq!(
+                pattern
+                    .find_overlapping_in(haystack, 1..6)
+                    .collect::<Vec<_>>(),
+                [1, 2]
+            );
+            assert!(pattern.find_overlapping_from(haystack, 10).next().is_none());
+        }
+    }
+
+    mod prefix {
+        use crate::KmpPattern;
+
+        #[test]
+        fn longest_prefix_at_end() {
+            let pattern = KmpPattern::new(b"abc");
+            assert_eq!(pattern.longest_prefix_at_end(b"xxab"), 2);
+            assert_eq!(pattern.longest_prefix_at_end(b"abcab"), 2);
+            assert_eq!(pattern.longest_prefix_at_end(b"xxabc"), 0);
+            assert_eq!(pattern.longest_prefix_at_end(b"xxa"), 1);
+            assert_eq!(pattern.longest_prefix_at_end(b""), 0);
+
+            let pattern = KmpPattern::new(b"aba");
+            assert_eq!(pattern.longest_prefix_at_end(b"aba"), 1);
+            assert_eq!(pattern.longest_prefix_at_end(b"abab"), 2);
+
+            assert_eq!(KmpPattern::<u8>::new(b"").longest_prefix_at_end(b"ab"), 0);
+        }
+
+        #[test]
+        fn matched_prefix_len() {
+            let pattern = KmpPattern::new(b"abc");
+            let mut search = pattern.find(b"abcab");
+            assert_eq!(search.next(), Some(0));
+            assert_eq!(search.matched_prefix_len(), 0);
+            assert_eq!(search.next(), None);
+            assert_eq!(search.matched_prefix_len(), 2);
+        }
+    }
+
+    mod indexed {
+        use crate::KmpPattern;
+
+        #[test]
+        fn narrow_table() {
+            let needle = b"abacabab";
+            let narrow = KmpPattern::<_, u16>::new_indexed(needle).unwrap();
+            let wide = KmpPattern::new(needle);
+
+            let narrow_offsets: Vec<_> = narrow.table().iter().map(|item| item.needle()).collect();
+            let wide_offsets: Vec<_> = wide.table().iter().map(|item| item.needle).collect();
+            assert_eq!(narrow_offsets, wide_offsets);
+            assert!(narrow.validate_table());
+
+            let haystack = b"abacababacabababacabab";
+            assert_eq!(
+                narrow.find_overlapping(haystack).collect::<Vec<_>>(),
+                wide.find_overlapping(haystack).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                narrow.find(haystack).rev().collect::<Vec<_>>(),
+                wide.find(haystack).rev().collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn needle_too_long() {
+            let needle = [0u8; 256];
+            assert!(KmpPattern::<_, u8>::new_indexed(&needle).is_none());
+            assert!(KmpPattern::<_, u8>::new_indexed(&needle[..255]).is_some());
+            assert!(KmpPattern::<_, u16>::new_indexed(&needle).is_some());
+        }
+    }
+
+    mod clone {
+        use crate::KmpPattern;
+
+        #[test]
+        fn clone_search() {
+            let pattern = KmpPattern::new(b"aba");
+            let mut search = pattern.find_overlapping(b"abababaxaba");
+            assert_eq!(search.next(), Some(0));
+
+            let forked = search.clone();
+            assert_eq!(search.next(), Some(2));
+            assert_eq!(search.collect::<Vec<_>>(), [4, 8]);
+            assert_eq!(forked.collect::<Vec<_>>(), [2, 4, 8]);
+        }
+
+        #[test]
+        fn clone_double_ended_search() {
+            let pattern = KmpPattern::new(b"ab");
+            let mut search = pattern.find(b"abxabyabzab");
+            assert_eq!(search.next_back(), Some(9));
+
+            let mut forked = search.clone();
+            assert_eq!(search.collect::<Vec<_>>(), [0, 3, 6]);
+            assert_eq!(forked.next_back(), Some(6));
+            assert_eq!(forked.collect::<Vec<_>>(), [0, 3]);
+        }
+
+        #[test]
+        fn clone_pattern() {
+            let pattern = KmpPattern::new(b"aa");
+            let cloned = pattern.clone();
+            drop(pattern);
+
+            assert_eq!(cloned.find(b"aaaa").collect::<Vec<_>>(), [0, 2]);
+        }
+    }
+
+    mod debug {
+        use crate::KmpPattern;
+
+        #[test]
+        fn debug_pattern() {
+            let pattern = KmpPattern::new(b"aa");
+            assert_eq!(
+                format!("{:?}", pattern),
+                "KmpPattern { needle: [97, 97], table: [\
+                 KmpTableItem { needle: 0, haystack: 0 }, \
+                 KmpTableItem { needle: 1, haystack: 0 }] }"
+            );
+        }
+
+        #[test]
+        fn debug_search() {
+            let pattern = KmpPattern::new(b"ab");
+            let mut search = pattern.find_overlapping(b"xaba");
+            search.next();
+            search.next();
+
+            assert_eq!(
+                format!("{:?}", search),
+                "KmpSearch { needle_pos: 1, haystack_pos: 4, overlapping: true, .. }"
+            );
+        }
+    }
+
+    mod state {
+        use crate::KmpPattern;
+
+        #[test]
+        fn failed_search() {
+            let pattern = KmpPattern::new(b"abcd");
+            let mut search = pattern.find(b"xxabxabc");
+
+            assert_eq!(search.state(), (0, 0));
+            assert_eq!(search.next(), None);
+            assert_eq!(search.state(), (3, 8));
+        }
+
+        #[test]
+        fn after_match() {
+            let pattern = KmpPattern::new(b"aa");
+            let mut search = pattern.find_overlapping(b"xaaa");
+
+            assert_eq!(search.next(), Some(1));
+            assert_eq!(search.state(), (1, 3));
+        }
+    }
+
+    mod reset {
+        use crate::KmpPattern;
+
+        #[test]
+        fn reset() {
+            let pattern = KmpPattern::new(b"aba");
+            let haystacks: [&[u8]; 4] = [b"abababa", b"xxab", b"abaxaba", b""];
+
+            let mut search = pattern.find_overlapping(b"ab");
+            for haystack in haystacks {
+                search.next();
+                search = search.reset(haystack);
+                assert_eq!(
+                    search.by_ref().collect::<Vec<_>>(),
+                    pattern.find_overlapping(haystack).collect::<Vec<_>>()
+                );
+            }
+        }
+
+        #[test]
+        fn reset_shorter_lifetime() {
+            let pattern = KmpPattern::new(b"ab");
+            let mut search = pattern.find(b"abab");
+            assert_eq!(search.next_back(), Some(2));
+
+            let haystack = b"xabyab".to_vec();
+            let search = search.reset(&haystack);
+            assert_eq!(search.collect::<Vec<_>>(), [1, 4]);
+        }
+    }
+
+    mod table {
+        use crate::{kmp_table as kd, KmpSearchable};
+
+        fn kmp_table<T: KmpSearchable>(needle: &[T]) -> Vec<usize> {
+            kd(needle).iter().map(|x| x.needle).collect()
+        }
+
+        #[test]
+        fn basic() {
+            assert_eq!(kmp_table(&['a', 'a', 'a', 'b']), vec![0, 1, 2, 0]);
         }
 
         #[test]
@@ -402,4 +2066,63 @@ mod tests {
             );
         }
     }
+
+    mod overflow {
+        use crate::{KmpPattern, KmpSearch, Wildcard};
+
+        fn huge() -> &'static [()] {
+            // SAFETY: `()` is zero-sized, so a dangling pointer is valid for any length.
+            unsafe {
+                core::slice::from_raw_parts(core::ptr::NonNull::dangling().as_ptr(), usize::MAX)
+            }
+        }
+
+        #[test]
+        fn empty_needle_at_max_len() {
+            let pattern = KmpPattern::<Wildcard<()>>::new(&[]);
+            let search = pattern.find(huge());
+            assert_eq!(search.size_hint(), (usize::MAX, None));
+
+            let mut search = KmpSearch {
+                haystack_pos: usize::MAX - 1,
+                ..search
+            };
+            assert_eq!(search.size_hint(), (2, Some(2)));
+            assert_eq!(search.next(), Some(usize::MAX - 1));
+            assert_eq!(search.next(), Some(usize::MAX));
+            assert_eq!(search.size_hint(), (0, Some(0)));
+            assert_eq!(search.next(), None);
+            assert_eq!(search.next(), None);
+            assert_eq!(search.matched_prefix_len(), 0);
+        }
+
+        #[test]
+        fn needle_at_max_len() {
+            let pattern = KmpPattern::new(&[Wildcard::<()>::Any; 2]);
+
+            let search = KmpSearch {
+                haystack_pos: usize::MAX - 3,
+                ..pattern.find_overlapping(huge())
+            };
+            assert_eq!(search.collect::<Vec<_>>(), [usize::MAX - 3, usize::MAX - 2]);
+
+            let search = KmpSearch {
+                haystack_pos: usize::MAX - 3,
+                ..pattern.find(huge())
+            };
+            assert_eq!(search.collect::<Vec<_>>(), [usize::MAX - 3]);
+        }
+
+        #[test]
+        fn foreign_state() {
+            let pattern = KmpPattern::new(b"abc");
+            let search = KmpSearch {
+                needle_pos: 2,
+                haystack_pos: 1,
+                ..pattern.find(b"abcabc")
+            };
+            assert_eq!(search.size_hint(), (0, Some(2)));
+            assert_eq!(search.clone().next_back(), Some(3));
+        }
+    }
 }
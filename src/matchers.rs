@@ -0,0 +1,523 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use crate::{KmpMatchable, KmpSearchable};
+
+/// Needle element matching a byte regardless of ASCII case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CaselessAscii(pub u8);
+
+impl CaselessAscii {
+    /// Needle matching `bytes` regardless of ASCII case.
+    pub fn needle(bytes: &[u8]) -> Vec<Self> {
+        bytes.iter().copied().map(Self).collect()
+    }
+
+    fn folded(&self) -> u8 {
+        self.0.to_ascii_lowercase()
+    }
+}
+
+impl KmpSearchable for CaselessAscii {
+    fn is_match_possible(&self, other: &Self) -> bool {
+        self.folded() == other.folded()
+    }
+
+    fn is_match_guaranteed(&self, other: &Self) -> bool {
+        self.folded() == other.folded()
+    }
+}
+
+impl KmpMatchable<CaselessAscii> for CaselessAscii {
+    fn match_haystack(&self, other: &CaselessAscii) -> bool {
+        self.folded() == other.folded()
+    }
+}
+
+impl KmpMatchable<u8> for CaselessAscii {
+    fn match_haystack(&self, other: &u8) -> bool {
+        self.0.eq_ignore_ascii_case(other)
+    }
+}
+
+/// Needle element matching either any haystack item or one exact value, as in masked byte
+/// signatures such as `48 8B ?? ?? 89`.
+///
+/// A match of `Any` is only possible where the needle expects `Exact`, never guaranteed, so
+/// the table re-reads the haystack items of such borders after a fallback instead of
+/// assuming they match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Wildcard<T> {
+    Any,
+    Exact(T),
+}
+
+impl<T: PartialEq> KmpSearchable for Wildcard<T> {
+    fn is_match_possible(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Wildcard::Exact(a), Wildcard::Exact(b)) => a == b,
+            _ => true,
+        }
+    }
+
+    fn is_match_guaranteed(&self, other: &Self) -> bool {
+        match (self, other) {
+            (_, Wildcard::Any) => true,
+            (Wildcard::Any, Wildcard::Exact(_)) => false,
+            (Wildcard::Exact(a), Wildcard::Exact(b)) => a == b,
+        }
+    }
+}
+
+impl<T: PartialEq> KmpMatchable<T> for Wildcard<T> {
+    fn match_haystack(&self, other: &T) -> bool {
+        match self {
+            Wildcard::Any => true,
+            Wildcard::Exact(value) => value == other,
+        }
+    }
+}
+
+/// Needle element matching any `char` from a set of ranges, like a regex class `[a-z0-9]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CharClass {
+    // Sorted, non-empty and non-adjacent, so every range of a subset lies within a single
+    // range of its superset.
+    ranges: Vec<RangeInclusive<char>>,
+}
+
+impl CharClass {
+    pub fn new<I: IntoIterator<Item = RangeInclusive<char>>>(ranges: I) -> Self {
+        let mut sorted: Vec<_> = ranges.into_iter().filter(|r| !r.is_empty()).collect();
+        sorted.sort_unstable_by_key(|r| *r.start());
+
+        let mut merged: Vec<RangeInclusive<char>> = Vec::with_capacity(sorted.len());
+        for range in sorted {
+            match merged.last_mut() {
+                Some(last) if *range.start() as u32 <= *last.end() as u32 + 1 => {
+                    if range.end() > last.end() {
+                        *last = *last.start()..=*range.end();
+                    }
+                }
+                _ => merged.push(range),
+            }
+        }
+
+        Self { ranges: merged }
+    }
+
+    pub fn single(c: char) -> Self {
+        Self {
+            ranges: vec![c..=c],
+        }
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        self.ranges.iter().any(|range| range.contains(&c))
+    }
+}
+
+impl KmpSearchable for CharClass {
+    fn is_match_possible(&self, other: &Self) -> bool {
+        self.ranges.iter().any(|a| {
+            other
+                .ranges
+                .iter()
+                .any(|b| a.start() <= b.end() && b.start() <= a.end())
+        })
+    }
+
+    fn is_match_guaranteed(&self, other: &Self) -> bool {
+        self.ranges.iter().all(|a| {
+            other
+                .ranges
+                .iter()
+                .any(|b| b.start() <= a.start() && a.end() <= b.end())
+        })
+    }
+}
+
+impl KmpMatchable<char> for CharClass {
+    fn match_haystack(&self, other: &char) -> bool {
+        self.contains(*other)
+    }
+}
+
+/// Needle element matching any haystack item except one value.
+///
+/// Any two `NotEqual` elements are assumed to be possibly matched by the same item, which
+/// only fails to hold for types with two values, such as `bool`. Overestimating possible
+/// matches just costs extra comparisons while searching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NotEqual<T>(pub T);
+
+impl<T: PartialEq> KmpSearchable for NotEqual<T> {
+    fn is_match_possible(&self, _other: &Self) -> bool {
+        true
+    }
+
+    fn is_match_guaranteed(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: PartialEq> KmpMatchable<T> for NotEqual<T> {
+    fn match_haystack(&self, other: &T) -> bool {
+        self.0 != *other
+    }
+}
+
+/// Needle element combining the other matchers, as built by
+/// [`KmpNeedleBuilder`](crate::KmpNeedleBuilder).
+///
+/// As with [`NotEqual`], `Not` elements are assumed to leave at least two values of `T`
+/// unmatched, and `Any` to match at least two values.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PatternElement<T> {
+    Exact(T),
+    Any,
+    OneOf(Vec<T>),
+    Not(T),
+}
+
+impl<T: PartialEq> KmpSearchable for PatternElement<T> {
+    fn is_match_possible(&self, other: &Self) -> bool {
+        use PatternElement::*;
+
+        match (self, other) {
+            (Exact(a), Exact(b)) => a == b,
+            (Exact(a), OneOf(set)) | (OneOf(set), Exact(a)) => set.contains(a),
+            (Exact(a), Not(b)) | (Not(b), Exact(a)) => a != b,
+            (OneOf(a), OneOf(b)) => a.iter().any(|value| b.contains(value)),
+            (OneOf(set), Not(b)) | (Not(b), OneOf(set)) => set.iter().any(|value| value != b),
+            (OneOf(set), Any) | (Any, OneOf(set)) => !set.is_empty(),
+            (Any | Not(_), Any | Not(_)) | (Exact(_), Any) | (Any, Exact(_)) => true,
+        }
+    }
+
+    fn is_match_guaranteed(&self, other: &Self) -> bool {
+        use PatternElement::*;
+
+        match (self, other) {
+            (_, Any) => true,
+            (Exact(a), Exact(b)) => a == b,
+            (Exact(a), OneOf(set)) => set.contains(a),
+            (Exact(a), Not(b)) => a != b,
+            (OneOf(set), Exact(b)) => set.iter().all(|value| value == b),
+            (OneOf(a), OneOf(b)) => a.iter().all(|value| b.contains(value)),
+            (OneOf(set), Not(b)) => set.iter().all(|value| value != b),
+            (Not(a), Not(b)) => a == b,
+            (Any | Not(_), _) => false,
+        }
+    }
+}
+
+impl<T: PartialEq> KmpMatchable<T> for PatternElement<T> {
+    fn match_haystack(&self, other: &T) -> bool {
+        match self {
+            PatternElement::Exact(value) => value == other,
+            PatternElement::Any => true,
+            PatternElement::OneOf(set) => set.contains(other),
+            PatternElement::Not(value) => value != other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::{wildcard_needle, xorshift};
+    use crate::{
+        CaselessAscii, CharClass, KmpMatchable, KmpPattern, KmpSearchable, NotEqual,
+        PatternElement, Wildcard,
+    };
+
+    fn naive_find_overlapping<N: KmpMatchable<H>, H>(needle: &[N], haystack: &[H]) -> Vec<usize> {
+        if needle.len() > haystack.len() {
+            return vec![];
+        }
+
+        (0..=haystack.len() - needle.len())
+            .filter(|&i| {
+                needle
+                    .iter()
+                    .zip(&haystack[i..])
+                    .all(|(needle_item, item)| needle_item.match_haystack(item))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn caseless_ascii() {
+        let needle = b"aBc".map(CaselessAscii);
+        let pattern = KmpPattern::new(&needle);
+
+        assert_eq!(pattern.find(b"XXABCXXabc").collect::<Vec<_>>(), [2, 7]);
+
+        let haystack = b"xxAbCxx".map(CaselessAscii);
+        assert_eq!(pattern.find(&haystack).collect::<Vec<_>>(), [2]);
+    }
+
+    #[test]
+    fn caseless_ascii_needle() {
+        let needle = CaselessAscii::needle(b"Content-Length");
+        let pattern = KmpPattern::new(&needle);
+
+        assert!(pattern.is_match(b"content-length: 4"));
+        assert!(pattern.is_match(b"CONTENT-LENGTH: 4"));
+        assert!(!pattern.is_match(b"content_length: 4"));
+    }
+
+    #[test]
+    fn caseless_ascii_table() {
+        let needle = b"aAb".map(CaselessAscii);
+        let pattern = KmpPattern::new(&needle);
+
+        assert_eq!(pattern.find(b"AAAB").collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    fn wildcard() {
+        let needle = wildcard_needle(b"a?b");
+        let pattern = KmpPattern::new(&needle);
+
+        assert_eq!(pattern.find(b"aXb").collect::<Vec<_>>(), [0]);
+        assert_eq!(pattern.find(b"aYb").collect::<Vec<_>>(), [0]);
+        assert!(pattern.find(b"ab").next().is_none());
+        assert_eq!(pattern.find(b"aaxb").collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    fn wildcard_signature() {
+        let needle = [0x48, 0x8b, 0, 0, 0x89].map(|b| match b {
+            0 => Wildcard::Any,
+            b => Wildcard::Exact(b),
+        });
+        let pattern = KmpPattern::new(&needle);
+        let haystack = [
+            0x90, 0x48, 0x8b, 0x48, 0x8b, 0x89, 0x89, 0x48, 0x8b, 1, 2, 3,
+        ];
+
+        assert_eq!(pattern.find(&haystack).collect::<Vec<_>>(), [1]);
+        assert!(Wildcard::Exact(0x48).is_match_guaranteed(&Wildcard::Any));
+        assert!(!Wildcard::Any.is_match_guaranteed(&Wildcard::Exact(0x48)));
+    }
+
+    #[test]
+    fn wildcard_matches_naive() {
+        let mut next = xorshift(0x9e37_79b9);
+
+        for needle in [
+            &b"a?a"[..],
+            b"?a",
+            b"a?",
+            b"??",
+            b"a?ab",
+            b"ab?ab",
+            b"?ab?a",
+            b"aa?aa",
+        ] {
+            let needle = wildcard_needle(needle);
+            let pattern = KmpPattern::new(&needle);
+
+            for _ in 0..200 {
+                let haystack: Vec<u8> = (0..next(20)).map(|_| b"abc"[next(3)]).collect();
+
+                assert_eq!(
+                    pattern.find_overlapping(&haystack).collect::<Vec<_>>(),
+                    naive_find_overlapping(&needle, &haystack),
+                    "needle: {:?}, haystack: {:?}",
+                    needle,
+                    haystack
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn char_class() {
+        let needle = [CharClass::new(['a'..='b']), CharClass::single('c')];
+        let pattern = KmpPattern::new(&needle);
+
+        assert_eq!(pattern.find(&['a', 'c']).collect::<Vec<_>>(), [0]);
+        assert_eq!(pattern.find(&['b', 'c']).collect::<Vec<_>>(), [0]);
+        assert!(pattern.find(&['c', 'c']).next().is_none());
+    }
+
+    #[test]
+    fn char_class_normalized() {
+        let class = CharClass::new(['d'..='f', 'a'..='b', 'c'..='c']);
+        assert_eq!(class, CharClass::new(['a'..='f']));
+        assert!(class.contains('e'));
+        assert!(!class.contains('g'));
+    }
+
+    #[test]
+    fn char_class_overlapping_classes() {
+        // `[ab]` and `[bc]` overlap without either containing the other, so the table
+        // must rewind over the items matched by them.
+        let ab = || CharClass::new(['a'..='b']);
+        let bc = || CharClass::new(['b'..='c']);
+        let needle = [ab(), bc(), ab(), CharClass::single('d')];
+        let pattern = KmpPattern::new(&needle);
+
+        let haystack: Vec<char> = "abbbd abcbd bbabd".chars().collect();
+        assert_eq!(
+            pattern.find_overlapping(&haystack).collect::<Vec<_>>(),
+            [1, 7]
+        );
+
+        // The only border of `c a [bc] [abc] a` is `[abc] a`, which is not a border of
+        // `c a [bc]` extended by one item.
+        let needle = [
+            CharClass::single('c'),
+            CharClass::single('a'),
+            bc(),
+            CharClass::new(['a'..='c']),
+            CharClass::single('a'),
+        ];
+        let pattern = KmpPattern::new(&needle);
+
+        let haystack: Vec<char> = "ccaccacaaca".chars().collect();
+        assert_eq!(
+            pattern.find_overlapping(&haystack).collect::<Vec<_>>(),
+            [1, 4]
+        );
+    }
+
+    /// Needle mixing exact bytes with [`NotEqual`] elements.
+    #[derive(Debug)]
+    enum Mixed {
+        Exact(u8),
+        Not(NotEqual<u8>),
+    }
+
+    impl KmpSearchable for Mixed {
+        fn is_match_possible(&self, other: &Self) -> bool {
+            match (self, other) {
+                (Mixed::Exact(a), Mixed::Exact(b)) => a == b,
+                (Mixed::Exact(a), Mixed::Not(b)) | (Mixed::Not(b), Mixed::Exact(a)) => {
+                    b.match_haystack(a)
+                }
+                (Mixed::Not(a), Mixed::Not(b)) => a.is_match_possible(b),
+            }
+        }
+
+        fn is_match_guaranteed(&self, other: &Self) -> bool {
+            match (self, other) {
+                (Mixed::Exact(a), Mixed::Exact(b)) => a == b,
+                (Mixed::Exact(a), Mixed::Not(b)) => b.match_haystack(a),
+                (Mixed::Not(_), Mixed::Exact(_)) => false,
+                (Mixed::Not(a), Mixed::Not(b)) => a.is_match_guaranteed(b),
+            }
+        }
+    }
+
+    impl KmpMatchable<u8> for Mixed {
+        fn match_haystack(&self, other: &u8) -> bool {
+            match self {
+                Mixed::Exact(value) => value == other,
+                Mixed::Not(not) => not.match_haystack(other),
+            }
+        }
+    }
+
+    /// Parses `!x` as `NotEqual(x)` and any other byte as itself.
+    fn mixed(pattern: &[u8]) -> Vec<Mixed> {
+        let mut needle = vec![];
+        let mut bytes = pattern.iter();
+
+        while let Some(&b) = bytes.next() {
+            needle.push(match b {
+                b'!' => Mixed::Not(NotEqual(*bytes.next().unwrap())),
+                b => Mixed::Exact(b),
+            });
+        }
+
+        needle
+    }
+
+    #[test]
+    fn not_equal() {
+        let needle = mixed(b"X!\nY");
+        let pattern = KmpPattern::new(&needle);
+
+        assert_eq!(pattern.find(b"XaY XbY").collect::<Vec<_>>(), [0, 4]);
+        assert!(pattern.find(b"X\nY").next().is_none());
+
+        let needle = [NotEqual(b'a'), NotEqual(b'b')];
+        let pattern = KmpPattern::new(&needle);
+        assert_eq!(
+            pattern.find_overlapping(b"bcab").collect::<Vec<_>>(),
+            [0, 1]
+        );
+    }
+
+    #[test]
+    fn not_equal_shift() {
+        // After `aa` fails on the `!a`, the second `a` can still start a match.
+        let needle = mixed(b"aa!ab");
+        let pattern = KmpPattern::new(&needle);
+        assert_eq!(pattern.find(b"aaaxb").collect::<Vec<_>>(), [1]);
+
+        let needle = mixed(b"!aab");
+        let pattern = KmpPattern::new(&needle);
+        assert_eq!(pattern.find(b"xaxab").collect::<Vec<_>>(), [2]);
+    }
+
+    #[test]
+    fn not_equal_matches_naive() {
+        let mut next = xorshift(0x85eb_ca6b);
+
+        for needle in [
+            &b"a!a"[..],
+            b"!aa",
+            b"a!b",
+            b"!a!a",
+            b"ab!ab",
+            b"!ba!b",
+            b"a!aa!a",
+        ] {
+            let needle = mixed(needle);
+            let pattern = KmpPattern::new(&needle);
+
+            for _ in 0..200 {
+                let haystack: Vec<u8> = (0..next(20)).map(|_| b"abc"[next(3)]).collect();
+
+                assert_eq!(
+                    pattern.find_overlapping(&haystack).collect::<Vec<_>>(),
+                    naive_find_overlapping(&needle, &haystack),
+                    "needle: {:?}, haystack: {:?}",
+                    needle,
+                    haystack
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn pattern_element_matches_naive() {
+        let mut next = xorshift(0x68e3_1da4);
+
+        let element = |next: &mut dyn FnMut(usize) -> usize| match next(4) {
+            0 => PatternElement::Exact(b"abc"[next(3)]),
+            1 => PatternElement::Any,
+            2 => PatternElement::OneOf((0..next(3)).map(|_| b"abc"[next(3)]).collect()),
+            _ => PatternElement::Not(b"abc"[next(3)]),
+        };
+
+        for _ in 0..2000 {
+            let needle: Vec<_> = (0..1 + next(5)).map(|_| element(&mut next)).collect();
+            let pattern = KmpPattern::new(&needle);
+            let haystack: Vec<u8> = (0..next(20)).map(|_| b"abc"[next(3)]).collect();
+
+            assert_eq!(
+                pattern.find_overlapping(&haystack).collect::<Vec<_>>(),
+                naive_find_overlapping(&needle, &haystack),
+                "needle: {:?}, haystack: {:?}",
+                needle,
+                haystack
+            );
+        }
+    }
+}
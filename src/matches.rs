@@ -0,0 +1,176 @@
+use core::iter::FusedIterator;
+use core::ops::Range;
+
+use crate::{KmpMatchable, KmpPattern, KmpSearch};
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Non-overlapping search yielding each match as a [`Match`].
+    pub fn find_matches<H>(&'a self, haystack: &'a [H]) -> KmpMatches<'a, N, H, false>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpMatches {
+            search: self.find(haystack),
+        }
+    }
+
+    /// Non-overlapping search yielding the start and matched subslice of each match, like
+    /// `str::match_indices`.
+    pub fn match_indices<H>(
+        &'a self,
+        haystack: &'a [H],
+    ) -> impl Iterator<Item = (usize, &'a [H])> + 'a
+    where
+        N: KmpMatchable<H>,
+    {
+        self.find_matches(haystack)
+            .map(|m| (m.start(), m.as_slice()))
+    }
+
+    /// Overlapping counterpart of [`KmpPattern::find_matches`].
+    pub fn find_matches_overlapping<H>(&'a self, haystack: &'a [H]) -> KmpMatches<'a, N, H, true>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpMatches {
+            search: self.find_overlapping(haystack),
+        }
+    }
+}
+
+/// A match of a pattern: its position in the haystack and the matched subslice.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Match<'h, H> {
+    start: usize,
+    slice: &'h [H],
+}
+
+// The match only holds a reference to the haystack, so `H` need not be `Clone`.
+impl<'h, H> Clone for Match<'h, H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'h, H> Copy for Match<'h, H> {}
+
+impl<'h, H> Match<'h, H> {
+    pub(crate) fn new(haystack: &'h [H], range: Range<usize>) -> Self {
+        Self {
+            start: range.start,
+            slice: &haystack[range],
+        }
+    }
+
+    /// Position of the first matched item in the haystack.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Position just past the last matched item in the haystack.
+    pub fn end(&self) -> usize {
+        self.start + self.slice.len()
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.start()..self.end()
+    }
+
+    /// The matched items of the haystack.
+    pub fn as_slice(&self) -> &'h [H] {
+        self.slice
+    }
+}
+
+/// Iterator over the [`Match`]es of a search.
+pub struct KmpMatches<'a, N, H, const OVERLAPPING: bool> {
+    search: KmpSearch<'a, N, H, OVERLAPPING>,
+}
+
+impl<'a, N, H, const OVERLAPPING: bool> Iterator for KmpMatches<'a, N, H, OVERLAPPING>
+where
+    N: KmpMatchable<H>,
+{
+    type Item = Match<'a, H>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.search.next()?;
+        let end = start + self.search.needle.len();
+        Some(Match::new(self.search.haystack, start..end))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.search.size_hint()
+    }
+}
+
+impl<'a, N, H, const OVERLAPPING: bool> FusedIterator for KmpMatches<'a, N, H, OVERLAPPING> where
+    N: KmpMatchable<H>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{KmpPattern, Wildcard};
+
+    #[test]
+    fn matches() {
+        let pattern = KmpPattern::new(b"aa");
+        let haystack = b"aaaxaa";
+
+        let matches: Vec<_> = pattern.find_matches(haystack).collect();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].start(), 0);
+        assert_eq!(matches[0].end(), 2);
+        assert_eq!(matches[1].range(), 4..6);
+        assert_eq!(matches[1].as_slice(), b"aa");
+
+        let ranges: Vec<_> = pattern
+            .find_matches_overlapping(haystack)
+            .map(|m| m.range())
+            .collect();
+        assert_eq!(
+            ranges,
+            pattern
+                .find_ranges_overlapping(haystack)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn match_indices() {
+        const TEST_CASES: &[(&str, &str)] = &[
+            ("ab", "abxabyab"),
+            ("aa", "aaaaa"),
+            ("abc", "xyz"),
+            ("", "abc"),
+        ];
+
+        for (needle, haystack) in TEST_CASES {
+            let pattern = KmpPattern::new(needle.as_bytes());
+            let expected: Vec<_> = haystack
+                .match_indices(needle)
+                .map(|(pos, matched)| (pos, matched.as_bytes()))
+                .collect();
+
+            assert_eq!(
+                pattern
+                    .match_indices(haystack.as_bytes())
+                    .collect::<Vec<_>>(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn fuzzy_slices() {
+        let needle = [Wildcard::Exact(b'a'), Wildcard::Any];
+        let pattern = KmpPattern::new(&needle);
+
+        let slices: Vec<_> = pattern
+            .find_matches(b"abxacay")
+            .map(|m| m.as_slice())
+            .collect();
+        assert_eq!(slices, [&b"ab"[..], b"ac", b"ay"]);
+    }
+}
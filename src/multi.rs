@@ -0,0 +1,247 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+use crate::{KmpMatchable, KmpPattern, KmpSearch, KmpSearchable};
+
+/// Number of haystack items each pattern consumes in turn in
+/// [`KmpMultiPattern::contains_any`].
+const CONTAINS_BLOCK: usize = 256;
+
+/// Several patterns searched over the same haystack in a single pass.
+pub struct KmpMultiPattern<'a, N> {
+    patterns: Vec<KmpPattern<'a, N>>,
+}
+
+impl<'a, N> KmpMultiPattern<'a, N> {
+    pub fn new<I>(needles: I) -> Self
+    where
+        I: IntoIterator<Item = &'a [N]>,
+        N: KmpSearchable,
+    {
+        Self::from_patterns(needles.into_iter().map(KmpPattern::new).collect())
+    }
+
+    pub fn from_patterns(patterns: Vec<KmpPattern<'a, N>>) -> Self {
+        Self { patterns }
+    }
+
+    pub fn patterns(&self) -> &[KmpPattern<'a, N>] {
+        &self.patterns
+    }
+
+    /// Non-overlapping matches of each pattern, as `(pattern_index, position)` pairs.
+    ///
+    /// Matches of different patterns may overlap. They are yielded by position, and matches
+    /// at the same position by pattern index.
+    pub fn find<H>(&'a self, haystack: &'a [H]) -> KmpMultiSearch<'a, N, H, false>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpMultiSearch::new(self.patterns.iter().map(|pattern| pattern.find(haystack)))
+    }
+
+    /// Returns `true` if any pattern occurs in `haystack`.
+    ///
+    /// The searches advance in lockstep, a block of haystack items at a time, so the scan
+    /// stops shortly after the end of the first match of any pattern instead of running
+    /// each search to completion.
+    pub fn contains_any<H>(&self, haystack: &[H]) -> bool
+    where
+        N: KmpMatchable<H>,
+    {
+        if self
+            .patterns
+            .iter()
+            .any(|pattern| pattern.needle.is_empty())
+        {
+            return true;
+        }
+
+        // The `(needle_pos, haystack_pos)` state of each search.
+        let mut states = vec![(0, 0); self.patterns.len()];
+        let mut end = 0;
+
+        while end < haystack.len() {
+            end = (end + CONTAINS_BLOCK).min(haystack.len());
+
+            for (pattern, state) in self.patterns.iter().zip(&mut states) {
+                let mut search: KmpSearch<'_, N, H, false> = KmpSearch {
+                    needle_pos: state.0,
+                    haystack_pos: state.1,
+                    ..KmpSearch::new(pattern.needle, &pattern.lsp, &haystack[..end])
+                };
+
+                if search.next().is_some() {
+                    return true;
+                }
+
+                *state = (search.needle_pos, search.haystack_pos);
+            }
+        }
+
+        false
+    }
+
+    /// Overlapping matches of each pattern, ordered as in [`KmpMultiPattern::find`].
+    pub fn find_overlapping<H>(&'a self, haystack: &'a [H]) -> KmpMultiSearch<'a, N, H, true>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpMultiSearch::new(
+            self.patterns
+                .iter()
+                .map(|pattern| pattern.find_overlapping(haystack)),
+        )
+    }
+}
+
+/// Iterator merging the matches of the patterns of a [`KmpMultiPattern`].
+pub struct KmpMultiSearch<'a, N, H, const OVERLAPPING: bool> {
+    // Each search along with its next match, if any.
+    searches: Vec<(KmpSearch<'a, N, H, OVERLAPPING>, Option<usize>)>,
+}
+
+impl<'a, N, H, const OVERLAPPING: bool> KmpMultiSearch<'a, N, H, OVERLAPPING>
+where
+    N: KmpMatchable<H>,
+{
+    fn new<I>(searches: I) -> Self
+    where
+        I: Iterator<Item = KmpSearch<'a, N, H, OVERLAPPING>>,
+    {
+        let searches = searches
+            .map(|mut search| {
+                let next = search.next();
+                (search, next)
+            })
+            .collect();
+
+        Self { searches }
+    }
+}
+
+impl<'a, N, H, const OVERLAPPING: bool> Iterator for KmpMultiSearch<'a, N, H, OVERLAPPING>
+where
+    N: KmpMatchable<H>,
+{
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, pos) = self
+            .searches
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (_, next))| Some((index, (*next)?)))
+            .min_by_key(|&(index, pos)| (pos, index))?;
+
+        let (search, next) = &mut self.searches[index];
+        *next = search.next();
+
+        Some((index, pos))
+    }
+}
+
+impl<'a, N, H, const OVERLAPPING: bool> FusedIterator for KmpMultiSearch<'a, N, H, OVERLAPPING> where
+    N: KmpMatchable<H>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use crate::{KmpMatchable, KmpMultiPattern, KmpPattern, KmpSearchable};
+
+    /// Needle byte counting its comparisons with haystack bytes.
+    struct Probe<'c> {
+        byte: u8,
+        calls: &'c Cell<usize>,
+    }
+
+    impl KmpSearchable for Probe<'_> {
+        fn is_match_possible(&self, other: &Self) -> bool {
+            self.byte == other.byte
+        }
+
+        fn is_match_guaranteed(&self, other: &Self) -> bool {
+            self.byte == other.byte
+        }
+    }
+
+    impl KmpMatchable<u8> for Probe<'_> {
+        fn match_haystack(&self, other: &u8) -> bool {
+            self.calls.set(self.calls.get() + 1);
+            self.byte == *other
+        }
+    }
+
+    #[test]
+    fn merged_order() {
+        let needles: [&[u8]; 3] = [b"abc", b"bc", b"ab"];
+        let patterns = KmpMultiPattern::new(needles);
+
+        assert_eq!(
+            patterns.find(b"abcxab").collect::<Vec<_>>(),
+            [(0, 0), (2, 0), (1, 1), (2, 4)]
+        );
+    }
+
+    #[test]
+    fn overlapping() {
+        let needles: [&[u8]; 3] = [b"aa", b"a", b"aaa"];
+        let patterns = KmpMultiPattern::new(needles);
+
+        assert_eq!(
+            patterns.find(b"aaa").collect::<Vec<_>>(),
+            [(0, 0), (1, 0), (2, 0), (1, 1), (1, 2)]
+        );
+        assert_eq!(
+            patterns.find_overlapping(b"aaa").collect::<Vec<_>>(),
+            [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (1, 2)]
+        );
+    }
+
+    #[test]
+    fn contains_any() {
+        let needles: [&[u8]; 3] = [b"abc", b"xyz", b"cab"];
+        let patterns = KmpMultiPattern::new(needles);
+
+        assert!(patterns.contains_any(b"__cab__"));
+        assert!(patterns.contains_any(b"ab_abc"));
+        assert!(!patterns.contains_any(b"ab_ca_xy"));
+        assert!(!patterns.contains_any(b""));
+        assert!(!KmpMultiPattern::<u8>::new([]).contains_any(b"abc"));
+        assert!(KmpMultiPattern::new([&b"q"[..], b""]).contains_any(b""));
+    }
+
+    #[test]
+    fn contains_any_stops_early() {
+        let calls = Cell::new(0);
+        let probes = |needle: &[u8]| -> Vec<Probe<'_>> {
+            needle
+                .iter()
+                .map(|&byte| Probe {
+                    byte,
+                    calls: &calls,
+                })
+                .collect()
+        };
+
+        let (first, last) = (probes(b"xyz"), probes(b"ab"));
+        let patterns =
+            KmpMultiPattern::from_patterns(vec![KmpPattern::new(&first), KmpPattern::new(&last)]);
+
+        let mut haystack = b"_ab".to_vec();
+        haystack.resize(1 << 20, b'_');
+
+        assert!(patterns.contains_any(&haystack));
+        assert!(calls.get() < 4 * super::CONTAINS_BLOCK);
+    }
+
+    #[test]
+    fn no_patterns() {
+        let patterns = KmpMultiPattern::<u8>::new([]);
+        assert!(patterns.find(b"abc").next().is_none());
+    }
+}
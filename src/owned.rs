@@ -0,0 +1,178 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use crate::is_valid_table;
+use crate::{
+    check_needle, kmp_table, KmpError, KmpMatchable, KmpOwnedTable, KmpPattern, KmpSearch,
+    KmpSearchable, KmpTable,
+};
+
+/// A compiled pattern that owns its needle, so it can be stored or returned freely.
+///
+/// With the `serde` feature, deserializing checks that the table has one item per needle
+/// item and offsets in range, as [`KmpPattern::validate_table`] does, so a corrupted table
+/// is rejected instead of making the search index out of bounds.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "KmpOwnedParts<N>"))]
+pub struct KmpOwnedPattern<N> {
+    needle: Vec<N>,
+    #[cfg_attr(feature = "serde", serde(rename = "table"))]
+    lsp: KmpOwnedTable,
+}
+
+/// Unchecked form of a deserialized [`KmpOwnedPattern`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct KmpOwnedParts<N> {
+    needle: Vec<N>,
+    table: KmpOwnedTable,
+}
+
+#[cfg(feature = "serde")]
+impl<N> TryFrom<KmpOwnedParts<N>> for KmpOwnedPattern<N> {
+    type Error = KmpError;
+
+    fn try_from(parts: KmpOwnedParts<N>) -> Result<Self, KmpError> {
+        if !is_valid_table(parts.needle.len(), &parts.table) {
+            return Err(KmpError::InvalidTable);
+        }
+
+        Ok(Self {
+            needle: parts.needle,
+            lsp: parts.table,
+        })
+    }
+}
+
+impl<N> KmpOwnedPattern<N> {
+    pub fn new(needle: Vec<N>) -> Self
+    where
+        N: KmpSearchable,
+    {
+        let lsp = kmp_table(&needle);
+
+        Self { needle, lsp }
+    }
+
+    /// Like [`KmpOwnedPattern::new`], first checking the needle as
+    /// [`KmpPattern::try_new`] does.
+    pub fn try_new(needle: Vec<N>) -> Result<Self, KmpError>
+    where
+        N: KmpSearchable,
+    {
+        check_needle(&needle)?;
+        Ok(Self::new(needle))
+    }
+
+    pub fn needle(&self) -> &[N] {
+        &self.needle
+    }
+
+    pub fn table(&self) -> KmpTable<'_> {
+        &self.lsp
+    }
+
+    /// Borrows this pattern as a [`KmpPattern`] without copying the needle or the table.
+    pub fn to_borrowed(&self) -> KmpPattern<'_, N> {
+        KmpPattern {
+            needle: &self.needle,
+            lsp: Cow::Borrowed(&self.lsp),
+        }
+    }
+
+    pub fn find<'a, H>(&'a self, haystack: &'a [H]) -> KmpSearch<'a, N, H, false>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpSearch::new(&self.needle, &self.lsp, haystack)
+    }
+
+    pub fn find_overlapping<'a, H>(&'a self, haystack: &'a [H]) -> KmpSearch<'a, N, H, true>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpSearch::new(&self.needle, &self.lsp, haystack)
+    }
+}
+
+impl<'a, N: Clone> KmpPattern<'a, N> {
+    /// Copies the needle into a [`KmpOwnedPattern`], reusing the table if it is already
+    /// owned.
+    pub fn into_owned(self) -> KmpOwnedPattern<N> {
+        KmpOwnedPattern {
+            needle: self.needle.to_vec(),
+            lsp: self.lsp.into_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::{KmpOwnedPattern, KmpPattern};
+
+    fn compile(needle: &str) -> KmpOwnedPattern<u8> {
+        KmpOwnedPattern::new(needle.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn owned_pattern() {
+        let pattern = compile("aa");
+
+        assert_eq!(pattern.find(b"aaaaa").collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(
+            pattern.find_overlapping(b"aaaaa").collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+        assert_eq!(pattern.table().len(), 2);
+    }
+
+    #[test]
+    fn to_borrowed() {
+        let owned = compile("abab");
+        let borrowed = owned.to_borrowed();
+        let fresh = KmpPattern::new(b"abab");
+
+        assert_eq!(borrowed.table(), fresh.table());
+        assert_eq!(
+            borrowed.find_overlapping(b"abababab").collect::<Vec<_>>(),
+            fresh.find_overlapping(b"abababab").collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn into_owned() {
+        fn compile_borrowed(needle: &str) -> KmpOwnedPattern<u8> {
+            KmpPattern::new(needle.as_bytes()).into_owned()
+        }
+
+        let owned = compile_borrowed("abab");
+        assert_eq!(owned.table(), KmpPattern::new(b"abab").table());
+
+        let matches = thread::spawn(move || owned.find(b"abababab").collect::<Vec<_>>())
+            .join()
+            .unwrap();
+        assert_eq!(matches, [0, 4]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        let pattern = compile("abab");
+        let serialized = serde_json::to_string(&pattern).unwrap();
+        let restored: KmpOwnedPattern<u8> = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(restored.needle(), pattern.needle());
+        assert_eq!(restored.table(), pattern.table());
+
+        let short = r#"{"needle":[97,98],"table":[{"needle":0,"haystack":0}]}"#;
+        assert!(serde_json::from_str::<KmpOwnedPattern<u8>>(short).is_err());
+
+        let out_of_range =
+            r#"{"needle":[97,98],"table":[{"needle":0,"haystack":0},{"needle":2,"haystack":0}]}"#;
+        assert!(serde_json::from_str::<KmpOwnedPattern<u8>>(out_of_range).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use rayon::prelude::*;
+
+use crate::{KmpMatchable, KmpPattern};
+
+/// Smallest number of match starts searched by a single task.
+const MIN_CHUNK_LEN: usize = 1 << 16;
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Non-overlapping search splitting `haystack` into chunks searched in parallel.
+    ///
+    /// Returns the positions yielded by [`KmpPattern::find`], in ascending order. Each chunk
+    /// is extended `needle.len() - 1` items into the next one so that matches straddling a
+    /// split are found, and every match start is reported by the chunk that contains it.
+    pub fn par_find<H>(&self, haystack: &[H]) -> Vec<usize>
+    where
+        N: KmpMatchable<H> + Sync,
+        H: Sync,
+    {
+        let chunk_len = (haystack.len() / rayon::current_num_threads()).max(MIN_CHUNK_LEN);
+        self.par_find_chunked(haystack, chunk_len)
+    }
+
+    fn par_find_chunked<H>(&self, haystack: &[H], chunk_len: usize) -> Vec<usize>
+    where
+        N: KmpMatchable<H> + Sync,
+        H: Sync,
+    {
+        let needle_len = self.needle.len();
+        if needle_len == 0 {
+            return (0..=haystack.len()).collect();
+        }
+
+        let starts = haystack.len().div_ceil(chunk_len);
+
+        // Chunks collect every occurrence starting in them, overlapping or not: whether a
+        // match is part of the non-overlapping set depends on the matches before it.
+        let chunks: Vec<Vec<usize>> = (0..starts)
+            .into_par_iter()
+            .map(|chunk| {
+                let start = chunk * chunk_len;
+                let end = (start + chunk_len + needle_len - 1).min(haystack.len());
+
+                self.find_overlapping(&haystack[start..end])
+                    .map(|pos| start + pos)
+                    .filter(|&pos| pos < start + chunk_len)
+                    .collect()
+            })
+            .collect();
+
+        // A match is yielded by `find` exactly when it starts after the previous one ends.
+        let mut matches = Vec::new();
+        let mut next_start = 0;
+
+        for pos in chunks.into_iter().flatten() {
+            if pos >= next_start {
+                matches.push(pos);
+                next_start = pos + needle_len;
+            }
+        }
+
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::{wildcard_needle, xorshift};
+    use crate::KmpPattern;
+
+    #[test]
+    fn par_find() {
+        let pattern = KmpPattern::new(b"aa");
+        assert_eq!(pattern.par_find(b"aaaaa"), [0, 2]);
+        assert!(pattern.par_find(b"").is_empty());
+        assert_eq!(KmpPattern::<u8>::new(b"").par_find(b"ab"), [0, 1, 2]);
+    }
+
+    #[test]
+    fn chunk_boundaries() {
+        let needle = b"abcab";
+        let pattern = KmpPattern::new(needle);
+
+        for chunk_len in 1..12 {
+            // Matches start on, just before and just after every chunk boundary.
+            let mut haystack = vec![b'x'; chunk_len * 8];
+            for boundary in (chunk_len..haystack.len() - needle.len()).step_by(chunk_len * 2) {
+                let start = boundary - boundary.min(2) + boundary % 3;
+                haystack[start..start + needle.len()].copy_from_slice(needle);
+            }
+            haystack.extend_from_slice(b"abcabcab");
+
+            assert_eq!(
+                pattern.par_find_chunked(&haystack, chunk_len),
+                pattern.find(&haystack).collect::<Vec<_>>(),
+                "chunk_len: {}",
+                chunk_len
+            );
+        }
+    }
+
+    #[test]
+    fn matches_sequential() {
+        let mut next = xorshift(0x1b87_3593);
+
+        for needle in [&b"aa"[..], b"aba", b"a?a", b"?b", b"abab"] {
+            let needle = wildcard_needle(needle);
+            let pattern = KmpPattern::new(&needle);
+
+            for _ in 0..100 {
+                let haystack: Vec<u8> = (0..next(64)).map(|_| b"ab"[next(2)]).collect();
+
+                for chunk_len in [1, 2, 3, 7, 64] {
+                    assert_eq!(
+                        pattern.par_find_chunked(&haystack, chunk_len),
+                        pattern.find(&haystack).collect::<Vec<_>>(),
+                        "needle: {:?}, haystack: {:?}, chunk_len: {}",
+                        needle,
+                        haystack,
+                        chunk_len
+                    );
+                }
+            }
+        }
+    }
+}
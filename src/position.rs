@@ -0,0 +1,106 @@
+use alloc::vec::Vec;
+use core::iter::Map;
+
+use crate::{KmpMatchable, KmpPattern, KmpSearch};
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Non-overlapping search yielding `map(pos)` for each match position.
+    pub fn find_mapped_positions<H, P, F>(
+        &'a self,
+        haystack: &'a [H],
+        map: F,
+    ) -> Map<KmpSearch<'a, N, H, false>, F>
+    where
+        N: KmpMatchable<H>,
+        F: Fn(usize) -> P,
+    {
+        self.find(haystack).map(map)
+    }
+}
+
+/// 1-based line and column of a position in a byte buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Precomputed newline offsets of a byte buffer for O(log n) position conversion.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(haystack: &[u8]) -> Self {
+        let line_starts = core::iter::once(0)
+            .chain(
+                haystack
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &byte)| byte == b'\n')
+                    .map(|(i, _)| i + 1),
+            )
+            .collect();
+
+        Self { line_starts }
+    }
+
+    /// Converts a 0-based offset into a 1-based line and column.
+    ///
+    /// A newline byte belongs to the line it terminates.
+    pub fn line_column(&self, pos: usize) -> LineColumn {
+        let line = self.line_starts.partition_point(|&start| start <= pos);
+
+        LineColumn {
+            line,
+            column: pos - self.line_starts[line - 1] + 1,
+        }
+    }
+
+    pub fn mapper(&self) -> impl Fn(usize) -> LineColumn + '_ {
+        |pos| self.line_column(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{KmpPattern, LineColumn, LineIndex};
+
+    #[test]
+    fn mapped_positions() {
+        let pattern = KmpPattern::new(b"ab");
+        let found: Vec<_> = pattern
+            .find_mapped_positions(b"abxab", |pos| pos + 1)
+            .collect();
+        assert_eq!(found, [1, 4]);
+    }
+
+    #[test]
+    fn line_column_mapper() {
+        let haystack = b"foo bar\nbaz foo\n\nfoo";
+        let index = LineIndex::new(haystack);
+        let pattern = KmpPattern::new(b"foo");
+
+        let found: Vec<_> = pattern
+            .find_mapped_positions(haystack, index.mapper())
+            .collect();
+        assert_eq!(
+            found,
+            [
+                LineColumn { line: 1, column: 1 },
+                LineColumn { line: 2, column: 5 },
+                LineColumn { line: 4, column: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn line_column_newline() {
+        let index = LineIndex::new(b"a\n\nb");
+        assert_eq!(index.line_column(1), LineColumn { line: 1, column: 2 });
+        assert_eq!(index.line_column(2), LineColumn { line: 2, column: 1 });
+        assert_eq!(index.line_column(3), LineColumn { line: 3, column: 1 });
+        assert_eq!(index.line_column(4), LineColumn { line: 3, column: 2 });
+    }
+}
@@ -0,0 +1,138 @@
+use core::iter::FusedIterator;
+use core::ops::Range;
+
+use crate::{KmpMatchable, KmpPattern, KmpSearch};
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Non-overlapping search yielding the haystack range covered by each match.
+    ///
+    /// Every needle item, fuzzy or not, matches exactly one haystack item, so each range
+    /// spans `needle.len()` items. The `haystack` offsets of the table only count items read
+    /// again after a fallback and never change the length of a match.
+    pub fn find_ranges<H>(&'a self, haystack: &'a [H]) -> KmpRanges<'a, N, H, false>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpRanges {
+            search: self.find(haystack),
+        }
+    }
+
+    /// Overlapping counterpart of [`KmpPattern::find_ranges`].
+    pub fn find_ranges_overlapping<H>(&'a self, haystack: &'a [H]) -> KmpRanges<'a, N, H, true>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpRanges {
+            search: self.find_overlapping(haystack),
+        }
+    }
+
+    /// Non-overlapping search yielding the matched subslices of `haystack`.
+    ///
+    /// The subslices are those covered by [`KmpPattern::find_ranges`].
+    pub fn find_slices<H>(&'a self, haystack: &'a [H]) -> impl Iterator<Item = &'a [H]> + 'a
+    where
+        N: KmpMatchable<H>,
+    {
+        self.find_ranges(haystack)
+            .map(move |range| &haystack[range])
+    }
+}
+
+/// Iterator over the haystack ranges covered by each match.
+pub struct KmpRanges<'a, N, H, const OVERLAPPING: bool> {
+    search: KmpSearch<'a, N, H, OVERLAPPING>,
+}
+
+impl<'a, N, H, const OVERLAPPING: bool> Iterator for KmpRanges<'a, N, H, OVERLAPPING>
+where
+    N: KmpMatchable<H>,
+{
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.search.next()?;
+        Some(start..start + self.search.needle.len())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.search.size_hint()
+    }
+}
+
+impl<'a, N, H, const OVERLAPPING: bool> FusedIterator for KmpRanges<'a, N, H, OVERLAPPING> where
+    N: KmpMatchable<H>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{KmpPattern, Wildcard};
+
+    #[test]
+    fn ranges() {
+        let pattern = KmpPattern::new(b"ab");
+        assert_eq!(
+            pattern.find_ranges(b"abxabyab").collect::<Vec<_>>(),
+            [0..2, 3..5, 6..8]
+        );
+    }
+
+    #[test]
+    fn ranges_overlapping() {
+        let pattern = KmpPattern::new(b"aa");
+        assert_eq!(
+            pattern.find_ranges(b"aaaaa").collect::<Vec<_>>(),
+            [0..2, 2..4]
+        );
+        assert_eq!(
+            pattern
+                .find_ranges_overlapping(b"aaaaa")
+                .collect::<Vec<_>>(),
+            [0..2, 1..3, 2..4, 3..5]
+        );
+    }
+
+    #[test]
+    fn fuzzy_spans() {
+        let needle = [Wildcard::Exact(b'a'), Wildcard::Any, Wildcard::Exact(b'a')];
+        let pattern = KmpPattern::new(&needle);
+
+        // The table falls back with items to read again, yet spans keep the needle length.
+        assert_ne!(pattern.table()[1].haystack(), 0);
+        assert_eq!(
+            pattern
+                .find_ranges_overlapping(b"aaaaxa")
+                .collect::<Vec<_>>(),
+            [0..3, 1..4, 3..6]
+        );
+        assert_eq!(
+            pattern.find_ranges(b"aaaaxa").collect::<Vec<_>>(),
+            [0..3, 3..6]
+        );
+    }
+
+    #[test]
+    fn slices() {
+        let pattern = KmpPattern::new(b"ab");
+        let haystack = b"abxabyab";
+
+        let slices: Vec<_> = pattern.find_slices(haystack).collect();
+        assert_eq!(slices, [b"ab"; 3]);
+
+        for (slice, pos) in slices.into_iter().zip(pattern.find(haystack)) {
+            assert_eq!(slice, &haystack[pos..pos + 2]);
+            assert!(core::ptr::eq(slice.as_ptr(), &haystack[pos]));
+        }
+    }
+
+    #[test]
+    fn ranges_empty_needle() {
+        let pattern = KmpPattern::<u8>::new(b"");
+        assert_eq!(
+            pattern.find_ranges(b"ab").collect::<Vec<_>>(),
+            [0..0, 1..1, 2..2]
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{KmpMatchable, KmpPattern, Match};
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Copies `haystack`, replacing every non-overlapping match with `replacement`.
+    ///
+    /// Matches are those of [`KmpPattern::find`] on the original haystack, so a replacement
+    /// containing the needle is not matched again. Like `str::replace`, the empty needle
+    /// matches before every item and at the end, so `replacement` is inserted between all
+    /// items and at both ends.
+    pub fn replace_all<H>(&self, haystack: &[H], replacement: &[H]) -> Vec<H>
+    where
+        N: KmpMatchable<H>,
+        H: Clone,
+    {
+        self.replace_n(haystack, replacement, usize::MAX)
+    }
+
+    /// Copies `haystack`, replacing the first match with `replacement`.
+    pub fn replace_first<H>(&self, haystack: &[H], replacement: &[H]) -> Vec<H>
+    where
+        N: KmpMatchable<H>,
+        H: Clone,
+    {
+        self.replace_n(haystack, replacement, 1)
+    }
+
+    /// Copies `haystack`, replacing every non-overlapping match with what `replace` returns
+    /// for it.
+    ///
+    /// Matches are those of [`KmpPattern::replace_all`], and `replace` is called on them in
+    /// order.
+    pub fn replace_with<'h, H, F, R>(&self, haystack: &'h [H], mut replace: F) -> Vec<H>
+    where
+        N: KmpMatchable<H>,
+        H: Clone,
+        F: FnMut(Match<'h, H>) -> R,
+        R: AsRef<[H]>,
+    {
+        let needle_len = self.needle.len();
+        let mut replaced = Vec::with_capacity(haystack.len());
+        let mut copied = 0;
+
+        for pos in self.find(haystack) {
+            let end = pos + needle_len;
+            replaced.extend_from_slice(&haystack[copied..pos]);
+            replaced.extend_from_slice(replace(Match::new(haystack, pos..end)).as_ref());
+            copied = end;
+        }
+
+        replaced.extend_from_slice(&haystack[copied..]);
+        replaced
+    }
+
+    fn replace_n<H>(&self, haystack: &[H], replacement: &[H], limit: usize) -> Vec<H>
+    where
+        N: KmpMatchable<H>,
+        H: Clone,
+    {
+        let needle_len = self.needle.len();
+        let mut replaced = Vec::with_capacity(haystack.len());
+        let mut copied = 0;
+
+        for pos in self.find(haystack).take(limit) {
+            replaced.extend_from_slice(&haystack[copied..pos]);
+            replaced.extend_from_slice(replacement);
+            copied = pos + needle_len;
+        }
+
+        replaced.extend_from_slice(&haystack[copied..]);
+        replaced
+    }
+}
+
+impl<'a> KmpPattern<'a, u8> {
+    /// Like [`KmpPattern::replace_all`] over the bytes of `haystack`, skipping matches that
+    /// do not start and end on `char` boundaries so that the result is valid UTF-8. The
+    /// search resumes right after the start of a skipped match, so a match on boundaries
+    /// overlapping it is still replaced.
+    ///
+    /// Matches of a needle that is itself valid UTF-8 always lie on boundaries, so this then
+    /// agrees with `str::replace`, the empty needle included.
+    pub fn replace_all_str(&self, haystack: &str, replacement: &str) -> String {
+        let needle_len = self.needle.len();
+        let mut replaced = String::with_capacity(haystack.len());
+        let mut copied = 0;
+        let mut search = self.find(haystack.as_bytes());
+
+        while let Some(pos) = search.next() {
+            let end = pos + needle_len;
+            if !haystack.is_char_boundary(pos) || !haystack.is_char_boundary(end) {
+                search = self.find_from(haystack.as_bytes(), pos + 1);
+                continue;
+            }
+
+            replaced.push_str(&haystack[copied..pos]);
+            replaced.push_str(replacement);
+            copied = end;
+        }
+
+        replaced.push_str(&haystack[copied..]);
+        replaced
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{KmpPattern, Wildcard};
+
+    #[test]
+    fn replace_all() {
+        let pattern = KmpPattern::new(b"ab");
+        assert_eq!(pattern.replace_all(b"abxaby", b"Z"), b"ZxZy");
+        assert_eq!(pattern.replace_all(b"abxaby", b"long"), b"longxlongy");
+        assert_eq!(pattern.replace_all(b"xyz", b"Z"), b"xyz");
+    }
+
+    #[test]
+    fn replace_first() {
+        let pattern = KmpPattern::new(b"ab");
+        assert_eq!(pattern.replace_first(b"abxaby", b"Z"), b"Zxaby");
+        assert_eq!(pattern.replace_first(b"xyz", b"Z"), b"xyz");
+    }
+
+    #[test]
+    fn deletion() {
+        let pattern = KmpPattern::new(b"aa");
+        assert_eq!(pattern.replace_all(b"aaaaa", b""), b"a");
+        assert_eq!(pattern.replace_first(b"baab", b""), b"bb");
+    }
+
+    #[test]
+    fn replacement_contains_needle() {
+        let pattern = KmpPattern::new(b"a");
+        assert_eq!(pattern.replace_all(b"bab", b"aa"), b"baab");
+    }
+
+    #[test]
+    fn empty_needle() {
+        let pattern = KmpPattern::<u8>::new(b"");
+        assert_eq!(pattern.replace_all(b"ab", b"-"), b"-a-b-");
+        assert_eq!(pattern.replace_all(b"", b"-"), b"-");
+        assert_eq!(pattern.replace_first(b"ab", b"-"), b"-ab");
+    }
+
+    #[test]
+    fn replace_with() {
+        let needle = [Wildcard::Exact(b'a'), Wildcard::Any];
+        let pattern = KmpPattern::new(&needle);
+        let replaced = pattern.replace_with(b"a1xa2a3", |m| {
+            let mut patched = m.as_slice().to_vec();
+            patched[1] += m.start() as u8;
+            patched
+        });
+        assert_eq!(replaced, b"a1xa5a8");
+
+        let pattern = KmpPattern::new(b"ab");
+        for haystack in [&b"abxaby"[..], b"aab", b"xyz", b""] {
+            assert_eq!(
+                pattern.replace_with(haystack, |_| b"Z"),
+                pattern.replace_all(haystack, b"Z")
+            );
+        }
+    }
+
+    #[test]
+    fn replace_all_str() {
+        const TEST_CASES: &[(&str, &str, &str)] = &[
+            ("ab", "abxaby", "Z"),
+            ("aa", "aaaaa", ""),
+            ("é", "caféé", "e"),
+            ("", "aé", "-"),
+            ("x", "", "-"),
+        ];
+
+        for (needle, haystack, replacement) in TEST_CASES {
+            let pattern = KmpPattern::new(needle.as_bytes());
+            assert_eq!(
+                pattern.replace_all_str(haystack, replacement),
+                haystack.replace(needle, replacement)
+            );
+        }
+
+        // The continuation byte of `é` never starts a character.
+        let pattern = KmpPattern::new(&[0xa9]);
+        assert_eq!(pattern.replace_all_str("é", "-"), "é");
+    }
+
+    #[test]
+    fn replace_all_str_invalid_needle() {
+        let haystack = "aéée\u{301}éa";
+
+        // Every match of these needles starts or ends inside a character, and skipping one
+        // does not hide the matches overlapping it.
+        for needle in [
+            &[0xa9, 0xc3][..],
+            &[0xc3, 0xa9, 0xc3],
+            &[b'e', 0xcc],
+            &[0x81, 0xc3],
+        ] {
+            let pattern = KmpPattern::new(needle);
+            assert!(pattern.find(haystack.as_bytes()).next().is_some());
+            assert_eq!(pattern.replace_all_str(haystack, "-"), haystack);
+        }
+    }
+}
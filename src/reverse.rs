@@ -0,0 +1,568 @@
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::Rev;
+
+use crate::{
+    item_matches, kmp_table_by, KmpIndex, KmpMatchable, KmpOwnedPattern, KmpOwnedTable, KmpPattern,
+    KmpSearch, KmpSearchable, KmpTableItem,
+};
+
+/// Table of the reversed needle, without copying the needle.
+pub(crate) fn kmp_table_rev<N: KmpSearchable>(needle: &[N]) -> KmpOwnedTable {
+    let reversed: Vec<&N> = needle.iter().rev().collect();
+
+    kmp_table_by(&reversed, |item, other| {
+        item.is_match_possible(other)
+            .then(|| item.is_match_guaranteed(other))
+    })
+}
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Compiles the reversed needle, for searching haystacks that are read back to front.
+    pub fn reversed(&self) -> KmpOwnedPattern<N>
+    where
+        N: KmpSearchable + Clone,
+    {
+        KmpOwnedPattern::new(self.needle.iter().rev().cloned().collect())
+    }
+
+    /// Start of the last match, found by scanning the haystack from the end.
+    ///
+    /// This builds the table of the reversed needle on each call and stops at the first
+    /// match from the end, so it only reads the haystack after the start of that match. The
+    /// empty needle matches last at `haystack.len()`.
+    pub fn rfind<H>(&self, haystack: &[H]) -> Option<usize>
+    where
+        N: KmpSearchable + KmpMatchable<H>,
+    {
+        let lsp = Cow::Owned(kmp_table_rev(self.needle));
+        let mut search = KmpBackSearch::<_, _, false>::new(self.needle, lsp, haystack);
+
+        search.next_by(item_matches)
+    }
+
+    /// Non-overlapping matches of [`KmpPattern::find`] in descending order.
+    ///
+    /// This is `find(haystack).rev()`: the table of the reversed needle is built on the
+    /// first match requested, and the haystack is scanned from the end only as far as the
+    /// matches consumed require.
+    pub fn find_rev<H>(&'a self, haystack: &'a [H]) -> Rev<KmpSearch<'a, N, H, false>>
+    where
+        N: KmpSearchable + KmpMatchable<H>,
+    {
+        self.find(haystack).rev()
+    }
+
+    /// Overlapping search yielding the start of every occurrence in descending order.
+    ///
+    /// The haystack is scanned from the end with the table of the reversed needle, built
+    /// once per call, so matches are produced without buffering. For non-overlapping matches
+    /// in descending order, use `find(haystack).rev()`, which yields the same matches as
+    /// [`KmpPattern::find`].
+    pub fn rfind_overlapping<H>(&'a self, haystack: &'a [H]) -> impl Iterator<Item = usize> + 'a
+    where
+        N: KmpSearchable + KmpMatchable<H>,
+    {
+        let lsp = Cow::Owned(kmp_table_rev(self.needle));
+        let mut search = KmpBackSearch::<_, _, true>::new(self.needle, lsp, haystack);
+
+        core::iter::from_fn(move || search.next_by(item_matches))
+    }
+}
+
+/// Back-to-front counterpart of [`KmpSearch`](crate::KmpSearch), driven by the table of the
+/// reversed needle and yielding match starts in descending order.
+pub(crate) struct KmpBackSearch<'a, N, H, const OVERLAPPING: bool> {
+    needle: &'a [N],
+    lsp: Cow<'a, [KmpTableItem]>,
+    haystack: &'a [H],
+    needle_pos: usize,
+    consumed: usize,
+}
+
+impl<'a, N, H, const OVERLAPPING: bool> Clone for KmpBackSearch<'a, N, H, OVERLAPPING> {
+    fn clone(&self) -> Self {
+        Self {
+            needle: self.needle,
+            lsp: self.lsp.clone(),
+            haystack: self.haystack,
+            needle_pos: self.needle_pos,
+            consumed: self.consumed,
+        }
+    }
+}
+
+impl<'a, N, H, const OVERLAPPING: bool> KmpBackSearch<'a, N, H, OVERLAPPING> {
+    pub(crate) fn new(needle: &'a [N], lsp: Cow<'a, [KmpTableItem]>, haystack: &'a [H]) -> Self {
+        Self {
+            needle,
+            lsp,
+            haystack,
+            needle_pos: 0,
+            consumed: 0,
+        }
+    }
+
+    pub(crate) fn next_by<M>(&mut self, mut matches: M) -> Option<usize>
+    where
+        M: FnMut(&N, &H) -> bool,
+    {
+        let needle_len = self.needle.len();
+        let haystack_len = self.haystack.len();
+
+        // As in the forward search, `needle_pos` marks the empty needle search as finished
+        // when `consumed` cannot move past a haystack of `usize::MAX` items.
+        if needle_len == 0 {
+            if self.consumed > haystack_len || self.needle_pos != 0 {
+                return None;
+            }
+
+            let pos = haystack_len - self.consumed;
+            match self.consumed.checked_add(1) {
+                Some(consumed) => self.consumed = consumed,
+                None => self.needle_pos = 1,
+            }
+            return Some(pos);
+        }
+
+        loop {
+            if self.consumed >= haystack_len {
+                return None;
+            }
+
+            self.consumed += 1;
+            let mut haystack_item = &self.haystack[haystack_len - self.consumed];
+
+            loop {
+                if matches(
+                    &self.needle[needle_len - 1 - self.needle_pos],
+                    haystack_item,
+                ) {
+                    self.needle_pos += 1;
+
+                    if self.needle_pos != needle_len {
+                        break;
+                    }
+
+                    let match_pos = haystack_len - self.consumed;
+
+                    if OVERLAPPING {
+                        let back = self.lsp[self.needle_pos - 1];
+                        self.needle_pos = back.needle;
+                        if back.haystack != 0 {
+                            self.needle_pos -= back.haystack;
+                            self.consumed -= back.haystack;
+                        }
+                    } else {
+                        self.needle_pos = 0;
+                    }
+
+                    return Some(match_pos);
+                }
+
+                if self.needle_pos == 0 {
+                    break;
+                }
+
+                let back = &self.lsp[self.needle_pos - 1];
+                self.needle_pos = back.needle;
+                if back.haystack != 0 {
+                    self.needle_pos -= back.haystack;
+                    self.consumed -= back.haystack;
+                    haystack_item = &self.haystack[haystack_len - self.consumed];
+                }
+            }
+        }
+    }
+}
+
+/// State of a non-overlapping [`KmpSearch`] consumed from the back.
+///
+/// Searching back to front picks different non-overlapping matches than the forward search
+/// whenever matches overlap, so overlapping matches are found from the back and grouped
+/// into runs of mutually overlapping matches. The forward search picks the first match of
+/// a run and then the next ones that do not overlap it, which only depends on the run, so
+/// the same matches are picked here and yielded in reverse. A run is buffered whole.
+pub(crate) struct KmpBack<'a, N, H> {
+    search: KmpBackSearch<'a, N, H, true>,
+    // Start of the part of the haystack left to the search when `next_back` was first
+    // called, which `search` covers.
+    lower: usize,
+    peeked: Option<usize>,
+    run: Vec<usize>,
+    last: Option<usize>,
+}
+
+impl<'a, N, H> Clone for KmpBack<'a, N, H> {
+    fn clone(&self) -> Self {
+        Self {
+            search: self.search.clone(),
+            lower: self.lower,
+            peeked: self.peeked,
+            run: self.run.clone(),
+            last: self.last,
+        }
+    }
+}
+
+impl<'a, N, H> KmpBack<'a, N, H> {
+    fn new(needle: &'a [N], haystack: &'a [H], lower: usize) -> Self
+    where
+        N: KmpSearchable,
+    {
+        let lower = lower.min(haystack.len());
+        let lsp = Cow::Owned(kmp_table_rev(needle));
+
+        Self {
+            search: KmpBackSearch::new(needle, lsp, &haystack[lower..]),
+            lower,
+            peeked: None,
+            run: Vec::new(),
+            last: None,
+        }
+    }
+
+    /// Returns `true` if a match at `pos` has already been returned from the back.
+    pub(crate) fn yielded(&self, pos: usize) -> bool {
+        self.last.is_some_and(|last| pos >= last)
+    }
+
+    /// Lowest match yielded from the back so far.
+    pub(crate) fn last(&self) -> Option<usize> {
+        self.last
+    }
+
+    fn next_occurrence(&mut self) -> Option<usize>
+    where
+        N: KmpMatchable<H>,
+    {
+        self.peeked.take().or_else(|| {
+            let pos = self.search.next_by(item_matches)?;
+            Some(pos + self.lower)
+        })
+    }
+
+    /// Buffers the matches picked in the next run of overlapping matches, in ascending
+    /// order.
+    fn fill_run(&mut self)
+    where
+        N: KmpMatchable<H>,
+    {
+        let needle_len = self.search.needle.len();
+        let Some(mut start) = self.next_occurrence() else {
+            return;
+        };
+
+        let mut occurrences = vec![start];
+        while let Some(pos) = self.next_occurrence() {
+            if pos + needle_len <= start {
+                self.peeked = Some(pos);
+                break;
+            }
+
+            occurrences.push(pos);
+            start = pos;
+        }
+
+        for pos in occurrences.into_iter().rev() {
+            if self.run.last().is_none_or(|&last| pos >= last + needle_len) {
+                self.run.push(pos);
+            }
+        }
+    }
+}
+
+impl<'a, N, H, I: KmpIndex> DoubleEndedIterator for KmpSearch<'a, N, H, false, I>
+where
+    N: KmpSearchable + KmpMatchable<H>,
+{
+    /// Returns the matches of the forward search in reverse, meeting the forward search in
+    /// the middle.
+    ///
+    /// The table of the reversed needle is built on the first call.
+    fn next_back(&mut self) -> Option<usize> {
+        if self.limit == 0 {
+            return None;
+        }
+
+        let lower = self.haystack_pos.saturating_sub(self.needle_pos);
+        let back = self
+            .back
+            .get_or_insert_with(|| Box::new(KmpBack::new(self.needle, self.haystack, lower)));
+
+        if back.run.is_empty() {
+            back.fill_run();
+        }
+
+        let pos = back.run.pop()?;
+        if self.front_last.is_some_and(|front| pos <= front) {
+            back.run.clear();
+            return None;
+        }
+
+        back.last = Some(pos);
+        self.limit -= 1;
+        Some(pos)
+    }
+}
+
+impl<'a, N, H, I: KmpIndex> DoubleEndedIterator for KmpSearch<'a, N, H, true, I>
+where
+    N: KmpSearchable + KmpMatchable<H>,
+{
+    /// Returns the occurrences of the forward search in reverse, meeting the forward search
+    /// in the middle.
+    ///
+    /// Every occurrence is yielded, so no run of overlapping matches needs to be buffered.
+    /// The table of the reversed needle is built on the first call.
+    fn next_back(&mut self) -> Option<usize> {
+        if self.limit == 0 {
+            return None;
+        }
+
+        let lower = self.haystack_pos.saturating_sub(self.needle_pos);
+        let back = self
+            .back
+            .get_or_insert_with(|| Box::new(KmpBack::new(self.needle, self.haystack, lower)));
+
+        let pos = back.next_occurrence()?;
+        if self.front_last.is_some_and(|front| pos <= front) {
+            return None;
+        }
+
+        back.last = Some(pos);
+        self.limit -= 1;
+        Some(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::borrow::Cow;
+    use alloc::vec::Vec;
+
+    use super::KmpBackSearch;
+    use crate::test_util::{wildcard_needle, xorshift};
+    use crate::{item_matches, KmpPattern, Wildcard};
+
+    #[test]
+    fn find_rev() {
+        let pattern = KmpPattern::new(b"aa");
+        assert_eq!(pattern.find_rev(b"aaaaxaa").collect::<Vec<_>>(), [5, 2, 0]);
+        assert_eq!(pattern.find_rev(b"aaa").next(), Some(0));
+    }
+
+    #[test]
+    fn rfind() {
+        let pattern = KmpPattern::new(b"ab");
+        assert_eq!(pattern.rfind(b"abXab"), Some(3));
+        assert_eq!(pattern.rfind(b"abXa"), Some(0));
+        assert_eq!(pattern.rfind(b"aXb"), None);
+        assert_eq!(pattern.rfind(b""), None);
+
+        let pattern = KmpPattern::new(b"aa");
+        assert_eq!(pattern.rfind(b"aaaa"), Some(2));
+    }
+
+    #[test]
+    fn rfind_overlapping() {
+        let pattern = KmpPattern::new(b"aa");
+        assert_eq!(
+            pattern.rfind_overlapping(b"aaaaa").collect::<Vec<_>>(),
+            [3, 2, 1, 0]
+        );
+        assert_eq!(
+            KmpPattern::<u8>::new(b"")
+                .rfind_overlapping(b"ab")
+                .collect::<Vec<_>>(),
+            [2, 1, 0]
+        );
+    }
+
+    #[test]
+    fn rfind_empty_needle() {
+        let pattern = KmpPattern::<u8>::new(b"");
+        assert_eq!(pattern.rfind(b"abc"), Some(3));
+        assert_eq!(pattern.rfind(b""), Some(0));
+    }
+
+    #[test]
+    fn rfind_matches_last_match() {
+        let mut next = xorshift(0x27d4_eb2f);
+
+        for needle in [&b"a?a"[..], b"ab", b"aab", b"?b", b"abab", b"b??b"] {
+            let needle = wildcard_needle(needle);
+            let pattern = KmpPattern::new(&needle);
+
+            for _ in 0..200 {
+                let haystack: Vec<u8> = (0..next(20)).map(|_| b"abc"[next(3)]).collect();
+
+                assert_eq!(
+                    pattern.rfind(&haystack),
+                    pattern
+                        .find_overlapping(&haystack)
+                        .fold(None, |_, pos| Some(pos)),
+                    "needle: {:?}, haystack: {:?}",
+                    needle,
+                    haystack
+                );
+
+                let mut expected: Vec<_> = pattern.find_overlapping(&haystack).collect();
+                expected.reverse();
+                assert_eq!(
+                    pattern.rfind_overlapping(&haystack).collect::<Vec<_>>(),
+                    expected,
+                    "needle: {:?}, haystack: {:?}",
+                    needle,
+                    haystack
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn reversed() {
+        let pattern = KmpPattern::new(b"aab");
+        let reversed = pattern.reversed();
+
+        assert_eq!(reversed.needle(), b"baa");
+        assert_eq!(reversed.find(b"xbaay").collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    fn double_ended() {
+        let pattern = KmpPattern::new(b"ab");
+        let haystack = b"abXabYab";
+
+        assert_eq!(pattern.find(haystack).collect::<Vec<_>>(), [0, 3, 6]);
+        assert_eq!(pattern.find(haystack).rev().collect::<Vec<_>>(), [6, 3, 0]);
+    }
+
+    #[test]
+    fn double_ended_overlapping_occurrences() {
+        let pattern = KmpPattern::new(b"aa");
+        assert_eq!(pattern.find(b"aaaaa").rev().collect::<Vec<_>>(), [2, 0]);
+        assert_eq!(pattern.find(b"aaa").rev().collect::<Vec<_>>(), [0]);
+        assert_eq!(pattern.find(b"aaaxaaa").rev().collect::<Vec<_>>(), [4, 0]);
+    }
+
+    #[test]
+    fn double_ended_meet_in_middle() {
+        let pattern = KmpPattern::new(b"ab");
+        let mut search = pattern.find(b"abXabYabZab");
+
+        assert_eq!(search.next(), Some(0));
+        assert_eq!(search.next_back(), Some(9));
+        assert_eq!(search.next_back(), Some(6));
+        assert_eq!(search.next(), Some(3));
+        assert_eq!(search.next(), None);
+        assert_eq!(search.next_back(), None);
+    }
+
+    #[test]
+    fn double_ended_from() {
+        let pattern = KmpPattern::new(b"ab");
+        assert_eq!(
+            pattern.find_from(b"abXabYab", 1).rev().collect::<Vec<_>>(),
+            [6, 3]
+        );
+
+        let empty = KmpPattern::<u8>::new(b"");
+        assert_eq!(empty.find(b"ab").rev().collect::<Vec<_>>(), [2, 1, 0]);
+        assert_eq!(empty.find_from(b"ab", 1).rev().collect::<Vec<_>>(), [2, 1]);
+    }
+
+    /// Drains `search` from both ends in an order picked by `next`, returning the matches
+    /// in ascending order.
+    fn interleaved<S>(mut search: S, next: &mut impl FnMut(usize) -> usize) -> Vec<usize>
+    where
+        S: DoubleEndedIterator<Item = usize>,
+    {
+        let mut front = vec![];
+        let mut back = vec![];
+        loop {
+            let found = if next(2) == 0 {
+                search.next().map(|pos| front.push(pos))
+            } else {
+                search.next_back().map(|pos| back.push(pos))
+            };
+
+            if found.is_none() {
+                break;
+            }
+        }
+
+        front.extend(search.by_ref());
+        front.extend(back.into_iter().rev());
+        front
+    }
+
+    #[test]
+    fn double_ended_matches_forward() {
+        let mut next = xorshift(0x1656_67b1);
+
+        for needle in [&b"a"[..], b"aa", b"aba", b"a?a", b"?a?", b"abab", b""] {
+            let needle = wildcard_needle(needle);
+            let pattern = KmpPattern::new(&needle);
+
+            for _ in 0..200 {
+                let haystack: Vec<u8> = (0..next(20)).map(|_| b"abc"[next(3)]).collect();
+
+                assert_eq!(
+                    interleaved(pattern.find(&haystack), &mut next),
+                    pattern.find(&haystack).collect::<Vec<_>>(),
+                    "needle: {:?}, haystack: {:?}",
+                    needle,
+                    haystack
+                );
+                assert_eq!(
+                    interleaved(pattern.find_overlapping(&haystack), &mut next),
+                    pattern.find_overlapping(&haystack).collect::<Vec<_>>(),
+                    "needle: {:?}, haystack: {:?}",
+                    needle,
+                    haystack
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn double_ended_overlapping() {
+        let pattern = KmpPattern::new(b"aa");
+        assert_eq!(
+            pattern
+                .find_overlapping(b"aaaxaa")
+                .rev()
+                .collect::<Vec<_>>(),
+            [4, 1, 0]
+        );
+
+        let mut search = pattern.find_overlapping(b"aaaa");
+        assert_eq!(search.next_back(), Some(2));
+        assert_eq!(search.next(), Some(0));
+        assert_eq!(search.next_back(), Some(1));
+        assert_eq!(search.next(), None);
+        assert_eq!(search.next_back(), None);
+    }
+
+    #[test]
+    fn empty_needle_at_max_len() {
+        // SAFETY: `()` is zero-sized, so a dangling pointer is valid for any length.
+        let haystack: &[()] = unsafe {
+            core::slice::from_raw_parts(core::ptr::NonNull::dangling().as_ptr(), usize::MAX)
+        };
+        let pattern = KmpPattern::<Wildcard<()>>::new(&[]);
+        assert_eq!(pattern.rfind(haystack), Some(usize::MAX));
+
+        let mut search = KmpBackSearch::<_, _, true> {
+            consumed: usize::MAX - 1,
+            ..KmpBackSearch::new(pattern.needle, Cow::Owned(Vec::new()), haystack)
+        };
+        assert_eq!(search.next_by(item_matches), Some(1));
+        assert_eq!(search.next_by(item_matches), Some(0));
+        assert_eq!(search.next_by(item_matches), None);
+        assert_eq!(search.next_by(item_matches), None);
+    }
+}
@@ -0,0 +1,177 @@
+use core::iter::FusedIterator;
+
+use crate::{KmpIndex, KmpMatchable, KmpPattern, KmpSearch, KmpSearchable};
+
+/// Whether a search reports overlapping matches, chosen at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Overlap {
+    /// Matches as yielded by [`KmpPattern::find`].
+    #[default]
+    NonOverlapping,
+    /// Matches as yielded by [`KmpPattern::find_overlapping`].
+    Overlapping,
+}
+
+impl<'a, N, I: KmpIndex> KmpPattern<'a, N, I> {
+    /// Configures a search whose overlap mode, start and match limit are picked at runtime.
+    pub fn search(&'a self) -> KmpSearchBuilder<'a, N, I> {
+        KmpSearchBuilder {
+            pattern: self,
+            overlap: Overlap::NonOverlapping,
+            start: 0,
+            limit: usize::MAX,
+        }
+    }
+}
+
+/// Builder of a [`KmpConfiguredSearch`], returned by [`KmpPattern::search`].
+pub struct KmpSearchBuilder<'a, N, I: KmpIndex = usize> {
+    pattern: &'a KmpPattern<'a, N, I>,
+    overlap: Overlap,
+    start: usize,
+    limit: usize,
+}
+
+// The builder only holds a reference to the pattern, so `N` need not be `Clone`.
+impl<'a, N, I: KmpIndex> Clone for KmpSearchBuilder<'a, N, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, N, I: KmpIndex> Copy for KmpSearchBuilder<'a, N, I> {}
+
+impl<'a, N, I: KmpIndex> KmpSearchBuilder<'a, N, I> {
+    pub fn overlap(self, overlap: Overlap) -> Self {
+        Self { overlap, ..self }
+    }
+
+    /// Starts searching at `start`, as [`KmpPattern::find_from`] does.
+    pub fn start(self, start: usize) -> Self {
+        Self { start, ..self }
+    }
+
+    /// Stops after `n` matches, see [`KmpSearch::limit`].
+    pub fn limit(self, n: usize) -> Self {
+        Self { limit: n, ..self }
+    }
+
+    pub fn build<H>(self, haystack: &'a [H]) -> KmpConfiguredSearch<'a, N, H, I>
+    where
+        N: KmpMatchable<H>,
+    {
+        match self.overlap {
+            Overlap::NonOverlapping => KmpConfiguredSearch::NonOverlapping(
+                self.pattern
+                    .find_from(haystack, self.start)
+                    .limit(self.limit),
+            ),
+            Overlap::Overlapping => KmpConfiguredSearch::Overlapping(
+                self.pattern
+                    .find_overlapping_from(haystack, self.start)
+                    .limit(self.limit),
+            ),
+        }
+    }
+}
+
+/// Search configured by a [`KmpSearchBuilder`], a single type for both overlap modes.
+#[derive(Debug)]
+pub enum KmpConfiguredSearch<'a, N, H, I = usize> {
+    NonOverlapping(KmpSearch<'a, N, H, false, I>),
+    Overlapping(KmpSearch<'a, N, H, true, I>),
+}
+
+// Like `KmpSearch`, cloning does not require `N` or `H` to be `Clone`.
+impl<'a, N, H, I> Clone for KmpConfiguredSearch<'a, N, H, I> {
+    fn clone(&self) -> Self {
+        match self {
+            KmpConfiguredSearch::NonOverlapping(search) => {
+                KmpConfiguredSearch::NonOverlapping(search.clone())
+            }
+            KmpConfiguredSearch::Overlapping(search) => {
+                KmpConfiguredSearch::Overlapping(search.clone())
+            }
+        }
+    }
+}
+
+impl<'a, N, H, I: KmpIndex> Iterator for KmpConfiguredSearch<'a, N, H, I>
+where
+    N: KmpMatchable<H>,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            KmpConfiguredSearch::NonOverlapping(search) => search.next(),
+            KmpConfiguredSearch::Overlapping(search) => search.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            KmpConfiguredSearch::NonOverlapping(search) => search.size_hint(),
+            KmpConfiguredSearch::Overlapping(search) => search.size_hint(),
+        }
+    }
+}
+
+impl<'a, N, H, I: KmpIndex> DoubleEndedIterator for KmpConfiguredSearch<'a, N, H, I>
+where
+    N: KmpSearchable + KmpMatchable<H>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            KmpConfiguredSearch::NonOverlapping(search) => search.next_back(),
+            KmpConfiguredSearch::Overlapping(search) => search.next_back(),
+        }
+    }
+}
+
+impl<'a, N, H, I: KmpIndex> FusedIterator for KmpConfiguredSearch<'a, N, H, I> where
+    N: KmpMatchable<H>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{KmpPattern, Overlap};
+
+    #[test]
+    fn overlap_modes() {
+        let pattern = KmpPattern::new(b"aa");
+        let haystack = b"aaaaxaa";
+
+        for (overlap, expected) in [
+            (Overlap::NonOverlapping, &[0, 2, 5][..]),
+            (Overlap::Overlapping, &[0, 1, 2, 5]),
+        ] {
+            let search = pattern.search().overlap(overlap).build(haystack);
+            assert_eq!(search.collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn start_and_limit() {
+        let pattern = KmpPattern::new(b"aa");
+        let builder = pattern.search().start(1).limit(2);
+
+        assert_eq!(builder.build(b"aaaaxaa").collect::<Vec<_>>(), [1, 5]);
+        assert_eq!(
+            builder
+                .overlap(Overlap::Overlapping)
+                .build(b"aaaaxaa")
+                .collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert_eq!(
+            builder
+                .overlap(Overlap::Overlapping)
+                .build(b"aaaaxaa")
+                .rev()
+                .collect::<Vec<_>>(),
+            [5, 2]
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use alloc::vec::Vec;
+
+use crate::{item_matches, KmpMatchable, KmpPattern, KmpSearch, KmpTableItem};
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Non-overlapping search over the concatenation of `segments`, without copying them.
+    ///
+    /// Positions are indices into the concatenation. Matches spanning segment boundaries are
+    /// found, including ones spanning several short or empty segments.
+    pub fn find_segments<H>(&'a self, segments: &'a [&'a [H]]) -> impl Iterator<Item = usize> + 'a
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpSegmentsSearch::<_, _, _, false>::new(self.needle, &self.lsp, segments)
+    }
+
+    /// Overlapping counterpart of [`KmpPattern::find_segments`].
+    pub fn find_segments_overlapping<H>(
+        &'a self,
+        segments: &'a [&'a [H]],
+    ) -> impl Iterator<Item = usize> + 'a
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpSegmentsSearch::<_, _, _, true>::new(self.needle, &self.lsp, segments)
+    }
+}
+
+/// Search over consecutive segments of a haystack.
+///
+/// Each segment is searched in place. When a segment is exhausted with a partial match
+/// pending, the items of that partial match and the next `needle.len() - 1` items are
+/// gathered by reference into `seam`, which is searched until the pending partial match
+/// lies within a single segment again.
+pub(crate) struct KmpSegmentsSearch<'a, N, H, S, const OVERLAPPING: bool> {
+    needle: &'a [N],
+    lsp: &'a [KmpTableItem],
+    segments: S,
+    len: usize,
+    // Segment being searched, or from which the seam continues, and its start position.
+    segment: usize,
+    segment_start: usize,
+    // Number of items of `segment` already gathered into the seam.
+    gathered: usize,
+    seam: Vec<&'a H>,
+    seam_start: usize,
+    in_seam: bool,
+    needle_pos: usize,
+    haystack_pos: usize,
+}
+
+impl<'a, N, H, S, const OVERLAPPING: bool> KmpSegmentsSearch<'a, N, H, S, OVERLAPPING>
+where
+    S: AsRef<[&'a [H]]> + Copy,
+{
+    pub(crate) fn new(needle: &'a [N], lsp: &'a [KmpTableItem], segments: S) -> Self {
+        let len = segments.as_ref().iter().map(|segment| segment.len()).sum();
+
+        Self {
+            needle,
+            lsp,
+            segments,
+            len,
+            segment: 0,
+            segment_start: 0,
+            gathered: 0,
+            seam: Vec::new(),
+            seam_start: 0,
+            in_seam: false,
+            needle_pos: 0,
+            haystack_pos: 0,
+        }
+    }
+
+    fn next_in<T, M>(&mut self, haystack: &[T], matches: M) -> Option<usize>
+    where
+        M: FnMut(&N, &T) -> bool,
+    {
+        let mut search: KmpSearch<'_, N, T, OVERLAPPING> = KmpSearch {
+            needle_pos: self.needle_pos,
+            haystack_pos: self.haystack_pos,
+            ..KmpSearch::new(self.needle, self.lsp, haystack)
+        };
+
+        let found = search.next_by(matches);
+        self.needle_pos = search.needle_pos;
+        self.haystack_pos = search.haystack_pos;
+
+        found
+    }
+
+    /// Appends up to `count` items following the seam, moving on to the next segments.
+    fn gather(&mut self, mut count: usize) {
+        let segments = self.segments;
+        let segments = segments.as_ref();
+
+        while count > 0 && self.segment < segments.len() {
+            let segment = segments[self.segment];
+            let taken = count.min(segment.len() - self.gathered);
+
+            self.seam
+                .extend(&segment[self.gathered..self.gathered + taken]);
+            self.gathered += taken;
+            count -= taken;
+
+            if self.gathered == segment.len() {
+                self.segment_start += segment.len();
+                self.segment += 1;
+                self.gathered = 0;
+            }
+        }
+    }
+
+    fn next_match(&mut self) -> Option<usize>
+    where
+        N: KmpMatchable<H>,
+    {
+        let segments = self.segments;
+        let segments = segments.as_ref();
+
+        loop {
+            if !self.in_seam {
+                let segment = *segments.get(self.segment)?;
+
+                if let Some(pos) = self.next_in(segment, item_matches) {
+                    return Some(self.segment_start + pos);
+                }
+
+                // The search consumed the whole segment, so the pending partial match is
+                // made of its last `needle_pos` items.
+                let pending = self.needle_pos;
+                self.seam.clear();
+                self.seam.extend(&segment[segment.len() - pending..]);
+                self.seam_start = self.segment_start + segment.len() - pending;
+                self.segment_start += segment.len();
+                self.segment += 1;
+                self.gathered = 0;
+            } else {
+                let seam = core::mem::take(&mut self.seam);
+                let found = self.next_in(&seam, |needle, item| item_matches(needle, item));
+                self.seam = seam;
+
+                if let Some(pos) = found {
+                    return Some(self.seam_start + pos);
+                }
+
+                if self.segment == segments.len() {
+                    return None;
+                }
+
+                // Once the pending partial match lies within the current segment, it is
+                // searched in place again.
+                if self.needle_pos <= self.gathered {
+                    self.haystack_pos = self.gathered;
+                    self.in_seam = false;
+                    continue;
+                }
+
+                let dropped = self.seam.len() - self.needle_pos;
+                self.seam.drain(..dropped);
+                self.seam_start += dropped;
+            }
+
+            // Any match including the pending items ends within the next
+            // `needle.len() - 1` items.
+            self.haystack_pos = self.needle_pos;
+            self.gather(self.needle.len() - 1);
+            self.in_seam = true;
+        }
+    }
+}
+
+impl<'a, N, H, S, const OVERLAPPING: bool> Iterator for KmpSegmentsSearch<'a, N, H, S, OVERLAPPING>
+where
+    N: KmpMatchable<H>,
+    S: AsRef<[&'a [H]]> + Copy,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.needle.is_empty() {
+            if self.haystack_pos > self.len {
+                return None;
+            }
+
+            self.haystack_pos += 1;
+            return Some(self.haystack_pos - 1);
+        }
+
+        self.next_match()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::{wildcard_needle, xorshift};
+    use crate::KmpPattern;
+
+    #[test]
+    fn across_joins() {
+        let segments: [&[u8]; 3] = [b"ab", b"cab", b"c"];
+
+        let pattern = KmpPattern::new(b"abc");
+        assert_eq!(pattern.find_segments(&segments).collect::<Vec<_>>(), [0, 3]);
+
+        let pattern = KmpPattern::new(b"bcabc");
+        assert_eq!(pattern.find_segments(&segments).collect::<Vec<_>>(), [1]);
+
+        let pattern = KmpPattern::new(b"cabc");
+        assert_eq!(pattern.find_segments(&segments).collect::<Vec<_>>(), [2]);
+    }
+
+    #[test]
+    fn empty_and_short_segments() {
+        let segments: [&[u8]; 7] = [b"", b"a", b"", b"b", b"a", b"", b"ba"];
+        let pattern = KmpPattern::new(b"aba");
+
+        assert_eq!(pattern.find_segments(&segments).collect::<Vec<_>>(), [0]);
+        assert_eq!(
+            pattern
+                .find_segments_overlapping(&segments)
+                .collect::<Vec<_>>(),
+            [0, 2]
+        );
+        assert!(pattern.find_segments::<u8>(&[]).next().is_none());
+        assert_eq!(
+            KmpPattern::<u8>::new(b"")
+                .find_segments(&segments)
+                .collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn matches_concatenation() {
+        let mut next = xorshift(0x5bd1_e995);
+
+        for needle in [&b"a"[..], b"aa", b"aba", b"a?a", b"?b", b"ab?ab", b"b??b"] {
+            let needle = wildcard_needle(needle);
+            let pattern = KmpPattern::new(&needle);
+
+            for _ in 0..200 {
+                let items: Vec<u8> = (0..next(24)).map(|_| b"ab"[next(2)]).collect();
+
+                let mut segments = Vec::new();
+                let mut rest = &items[..];
+                while !rest.is_empty() || next(4) == 0 {
+                    let (segment, tail) = rest.split_at(next(4).min(rest.len()));
+                    segments.push(segment);
+                    rest = tail;
+                }
+
+                assert_eq!(
+                    pattern.find_segments(&segments).collect::<Vec<_>>(),
+                    pattern.all_matches(&items),
+                    "needle: {:?}, segments: {:?}",
+                    needle,
+                    segments
+                );
+                assert_eq!(
+                    pattern
+                        .find_segments_overlapping(&segments)
+                        .collect::<Vec<_>>(),
+                    pattern.all_matches_overlapping(&items),
+                    "needle: {:?}, segments: {:?}",
+                    needle,
+                    segments
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use crate::{KmpMatchable, KmpPattern, KmpSearch, KmpTableItem};
+
+/// Automaton state of a [`KmpSearch`], sufficient to resume the scan later.
+///
+/// A state is only meaningful for the needle, table, haystack and overlapping mode it was
+/// taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KmpState {
+    needle_pos: usize,
+    haystack_pos: usize,
+}
+
+impl KmpState {
+    pub fn needle_pos(&self) -> usize {
+        self.needle_pos
+    }
+
+    pub fn haystack_pos(&self) -> usize {
+        self.haystack_pos
+    }
+}
+
+impl<'a, N, H, const OVERLAPPING: bool> KmpSearch<'a, N, H, OVERLAPPING> {
+    pub fn resume(
+        needle: &'a [N],
+        lsp: &'a [KmpTableItem],
+        haystack: &'a [H],
+        state: KmpState,
+    ) -> Self {
+        Self {
+            needle_pos: state.needle_pos,
+            haystack_pos: state.haystack_pos,
+            ..Self::new(needle, lsp, haystack)
+        }
+    }
+
+    /// Position of the automaton, from which [`KmpSearch::resume`] continues forward.
+    ///
+    /// Only the forward cursor is recorded: the [`KmpSearch::limit`] and any state from
+    /// [`DoubleEndedIterator::next_back`] are not. Resuming therefore yields the same
+    /// matches as continuing only for forward, unlimited searches; other searches resume
+    /// unlimited and without their back end.
+    pub fn snapshot(&self) -> KmpState {
+        KmpState {
+            needle_pos: self.needle_pos,
+            haystack_pos: self.haystack_pos,
+        }
+    }
+
+    pub fn with_snapshots(self) -> KmpSnapshots<'a, N, H, OVERLAPPING> {
+        KmpSnapshots { search: self }
+    }
+}
+
+impl<'a, N> KmpPattern<'a, N> {
+    pub fn find_with_snapshots<H>(&'a self, haystack: &'a [H]) -> KmpSnapshots<'a, N, H, false>
+    where
+        N: KmpMatchable<H>,
+    {
+        self.find(haystack).with_snapshots()
+    }
+
+    pub fn find_overlapping_with_snapshots<H>(
+        &'a self,
+        haystack: &'a [H],
+    ) -> KmpSnapshots<'a, N, H, true>
+    where
+        N: KmpMatchable<H>,
+    {
+        self.find_overlapping(haystack).with_snapshots()
+    }
+
+    /// Continues a non-overlapping search from a state taken by [`KmpSearch::snapshot`].
+    pub fn resume<H>(&'a self, haystack: &'a [H], state: KmpState) -> KmpSearch<'a, N, H, false>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpSearch::resume(self.needle, &self.lsp, haystack, state)
+    }
+
+    /// Continues an overlapping search from a state taken by [`KmpSearch::snapshot`].
+    pub fn resume_overlapping<H>(
+        &'a self,
+        haystack: &'a [H],
+        state: KmpState,
+    ) -> KmpSearch<'a, N, H, true>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpSearch::resume(self.needle, &self.lsp, haystack, state)
+    }
+}
+
+/// Iterator yielding each match together with the automaton state right after it.
+pub struct KmpSnapshots<'a, N, H, const OVERLAPPING: bool> {
+    search: KmpSearch<'a, N, H, OVERLAPPING>,
+}
+
+impl<'a, N, H, const OVERLAPPING: bool> Iterator for KmpSnapshots<'a, N, H, OVERLAPPING>
+where
+    N: KmpMatchable<H>,
+{
+    type Item = (usize, KmpState);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.search.next()?;
+        Some((pos, self.search.snapshot()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::xorshift;
+    use crate::KmpPattern;
+
+    #[test]
+    fn resume_from_every_snapshot() {
+        let haystack = b"abaababaabaababaab";
+        let pattern = KmpPattern::new(b"abaab");
+
+        let all: Vec<_> = pattern.find_overlapping(haystack).collect();
+        let snapshots: Vec<_> = pattern.find_overlapping_with_snapshots(haystack).collect();
+        assert_eq!(
+            all,
+            snapshots.iter().map(|(pos, _)| *pos).collect::<Vec<_>>()
+        );
+
+        for (i, (_, state)) in snapshots.iter().enumerate() {
+            let rest: Vec<_> = pattern.resume_overlapping(haystack, *state).collect();
+            assert_eq!(rest, all[i + 1..]);
+        }
+    }
+
+    #[test]
+    fn resume_non_overlapping() {
+        let haystack = b"aaaaaaa";
+        let pattern = KmpPattern::new(b"aa");
+
+        let snapshots: Vec<_> = pattern.find_with_snapshots(haystack).collect();
+        assert_eq!(
+            snapshots.iter().map(|(pos, _)| *pos).collect::<Vec<_>>(),
+            [0, 2, 4]
+        );
+
+        let rest: Vec<_> = pattern.resume(haystack, snapshots[0].1).collect();
+        assert_eq!(rest, [2, 4]);
+    }
+
+    #[test]
+    fn resume_matches_continuing() {
+        let mut next = xorshift(0x7f4a_7c15);
+
+        for _ in 0..200 {
+            let needle: Vec<u8> = (0..1 + next(4)).map(|_| b"ab"[next(2)]).collect();
+            let haystack: Vec<u8> = (0..next(24)).map(|_| b"ab"[next(2)]).collect();
+            let pattern = KmpPattern::new(&needle);
+
+            let mut search = pattern.find(&haystack);
+            while search.next().is_some() {
+                let resumed: Vec<_> = pattern.resume(&haystack, search.snapshot()).collect();
+                assert_eq!(resumed, search.clone().collect::<Vec<_>>());
+            }
+
+            let mut search = pattern.find_overlapping(&haystack);
+            while search.next().is_some() {
+                let resumed: Vec<_> = pattern
+                    .resume_overlapping(&haystack, search.snapshot())
+                    .collect();
+                assert_eq!(resumed, search.clone().collect::<Vec<_>>());
+            }
+        }
+    }
+
+    #[test]
+    fn snapshot_drops_limit_and_back() {
+        let pattern = KmpPattern::new(b"a");
+
+        let mut search = pattern.find(b"aaaa").limit(2);
+        assert_eq!(search.next(), Some(0));
+        assert_eq!(search.clone().count(), 1);
+        let resumed: Vec<_> = pattern.resume(b"aaaa", search.snapshot()).collect();
+        assert_eq!(resumed, [1, 2, 3]);
+
+        let mut search = pattern.find(b"aaaa");
+        assert_eq!(search.next_back(), Some(3));
+        let resumed: Vec<_> = pattern.resume(b"aaaa", search.snapshot()).collect();
+        assert_eq!(resumed, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn resume_empty_needle() {
+        let pattern = KmpPattern::<u8>::new(b"");
+        let snapshots: Vec<_> = pattern.find_with_snapshots(b"ab").collect();
+        assert_eq!(snapshots.len(), 3);
+
+        let rest: Vec<_> = pattern.resume(b"ab", snapshots[1].1).collect();
+        assert_eq!(rest, [2]);
+    }
+}
@@ -0,0 +1,276 @@
+use core::iter::FusedIterator;
+
+use crate::{KmpMatchable, KmpPattern, KmpSearch};
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Subslices of `haystack` separated by non-overlapping matches, like `str::split`.
+    ///
+    /// Adjacent matches and matches at either end of the haystack yield empty subslices.
+    /// The empty needle matches before every item and at the end, so the items are yielded
+    /// one by one between two empty subslices.
+    pub fn split<H>(&'a self, haystack: &'a [H]) -> KmpSplit<'a, N, H>
+    where
+        N: KmpMatchable<H>,
+    {
+        self.splitn(haystack, usize::MAX)
+    }
+
+    /// Like [`KmpPattern::split`], but yields at most `n` subslices, the last of which is
+    /// the unsplit remainder of the haystack.
+    pub fn splitn<H>(&'a self, haystack: &'a [H], n: usize) -> KmpSplit<'a, N, H>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpSplit {
+            search: self.find(haystack),
+            start: 0,
+            remaining: n,
+        }
+    }
+
+    /// Walks `haystack` as non-overlapping matches interleaved with the non-empty runs of
+    /// items between them, in order.
+    ///
+    /// Unlike [`KmpPattern::split`], empty gaps between adjacent matches or at either end of
+    /// the haystack are skipped.
+    pub fn split_keep<H>(&'a self, haystack: &'a [H]) -> impl Iterator<Item = Segment<'a, H>>
+    where
+        N: KmpMatchable<H>,
+    {
+        let needle_len = self.needle.len();
+        let mut search = self.find(haystack);
+        let mut start = 0;
+        let mut pending = None;
+
+        core::iter::from_fn(move || {
+            if pending.is_none() {
+                let end = match search.next() {
+                    Some(pos) => {
+                        pending = Some(pos);
+                        pos
+                    }
+                    None => haystack.len(),
+                };
+
+                if start < end {
+                    let gap = &haystack[start..end];
+                    start = end;
+                    return Some(Segment::Unmatched(gap));
+                }
+            }
+
+            let pos = pending.take()?;
+            start = pos + needle_len;
+            Some(Segment::Matched { pos })
+        })
+    }
+
+    /// Subslices of `haystack` each ending with a non-overlapping match, like
+    /// `str::split_inclusive`, for framing where the terminator is part of the frame.
+    ///
+    /// The items after the last match form a final subslice if there are any, so a haystack
+    /// ending with a match yields no trailing empty subslice.
+    pub fn split_inclusive<H>(&'a self, haystack: &'a [H]) -> impl Iterator<Item = &'a [H]> + 'a
+    where
+        N: KmpMatchable<H>,
+    {
+        let needle_len = self.needle.len();
+        let mut search = self.find(haystack);
+        let mut start = 0;
+
+        core::iter::from_fn(move || {
+            let end = match search.next() {
+                Some(pos) => pos + needle_len,
+                None if start < haystack.len() => haystack.len(),
+                None => return None,
+            };
+
+            let piece = &haystack[start..end];
+            start = end;
+            Some(piece)
+        })
+    }
+}
+
+/// Part of a haystack yielded by [`KmpPattern::split_keep`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'a, H> {
+    /// Non-empty run of items between matches.
+    Unmatched(&'a [H]),
+    /// Match starting at `pos`.
+    Matched { pos: usize },
+}
+
+/// Iterator over the subslices between the matches of a pattern.
+pub struct KmpSplit<'a, N, H> {
+    search: KmpSearch<'a, N, H, false>,
+    start: usize,
+    remaining: usize,
+}
+
+impl<'a, N, H> Iterator for KmpSplit<'a, N, H>
+where
+    N: KmpMatchable<H>,
+{
+    type Item = &'a [H];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let haystack = self.search.haystack;
+
+        match self.remaining {
+            0 => return None,
+            1 => {}
+            _ => {
+                if let Some(pos) = self.search.next() {
+                    let piece = &haystack[self.start..pos];
+                    self.start = pos + self.search.needle.len();
+                    self.remaining -= 1;
+                    return Some(piece);
+                }
+            }
+        }
+
+        self.remaining = 0;
+        Some(&haystack[self.start..])
+    }
+}
+
+impl<'a, N, H> FusedIterator for KmpSplit<'a, N, H> where N: KmpMatchable<H> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{KmpPattern, Segment};
+
+    fn split<'a>(pattern: &'a KmpPattern<u8>, haystack: &'a [u8]) -> Vec<&'a [u8]> {
+        pattern.split(haystack).collect()
+    }
+
+    #[test]
+    fn split_gaps() {
+        let pattern = KmpPattern::new(b"ab");
+        assert_eq!(
+            split(&pattern, b"abXXabYYab"),
+            [&b""[..], b"XX", b"YY", b""]
+        );
+        assert_eq!(split(&pattern, b"xababy"), [&b"x"[..], b"", b"y"]);
+        assert_eq!(split(&pattern, b"xyz"), [b"xyz"]);
+        assert_eq!(split(&pattern, b""), [b""]);
+    }
+
+    #[test]
+    fn split_records() {
+        let pattern = KmpPattern::new(&[0xffffu16, 0]);
+        let records = [1, 2, 0xffff, 0, 3, 0xffff, 0xffff, 0, 0xffff, 0];
+
+        assert_eq!(
+            pattern.split(&records).collect::<Vec<_>>(),
+            [&[1, 2][..], &[3, 0xffff], &[], &[]]
+        );
+    }
+
+    #[test]
+    fn splitn() {
+        let pattern = KmpPattern::new(b",");
+        let haystack = b"a,b,c";
+
+        assert!(pattern.splitn(haystack, 0).next().is_none());
+        assert_eq!(pattern.splitn(haystack, 1).collect::<Vec<_>>(), [b"a,b,c"]);
+        assert_eq!(
+            pattern.splitn(haystack, 2).collect::<Vec<_>>(),
+            [&b"a"[..], b"b,c"]
+        );
+        assert_eq!(
+            pattern.splitn(haystack, 10).collect::<Vec<_>>(),
+            [b"a", b"b", b"c"]
+        );
+    }
+
+    #[test]
+    fn split_empty_needle() {
+        let pattern = KmpPattern::<u8>::new(b"");
+        assert_eq!(split(&pattern, b"ab"), [&b""[..], b"a", b"b", b""]);
+        assert_eq!(
+            pattern.splitn(b"ab", 2).collect::<Vec<_>>(),
+            [&b""[..], b"ab"]
+        );
+    }
+
+    #[test]
+    fn split_keep() {
+        let pattern = KmpPattern::new(b"ab");
+
+        assert_eq!(
+            pattern.split_keep(b"abXab").collect::<Vec<_>>(),
+            [
+                Segment::Matched { pos: 0 },
+                Segment::Unmatched(b"X"),
+                Segment::Matched { pos: 3 },
+            ]
+        );
+        assert_eq!(
+            pattern.split_keep(b"XYababZ").collect::<Vec<_>>(),
+            [
+                Segment::Unmatched(b"XY"),
+                Segment::Matched { pos: 2 },
+                Segment::Matched { pos: 4 },
+                Segment::Unmatched(b"Z"),
+            ]
+        );
+        assert_eq!(
+            pattern.split_keep(b"XYZ").collect::<Vec<_>>(),
+            [Segment::Unmatched(b"XYZ")]
+        );
+        assert!(pattern.split_keep(b"").next().is_none());
+    }
+
+    #[test]
+    fn split_keep_empty_needle() {
+        let pattern = KmpPattern::<u8>::new(b"");
+
+        assert_eq!(
+            pattern.split_keep(b"ab").collect::<Vec<_>>(),
+            [
+                Segment::Matched { pos: 0 },
+                Segment::Unmatched(b"a"),
+                Segment::Matched { pos: 1 },
+                Segment::Unmatched(b"b"),
+                Segment::Matched { pos: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn matches_str_split() {
+        for (needle, haystack) in [("ab", "abXXabYYab"), ("aa", "aaaaa"), ("x", "axbxxc")] {
+            let pattern = KmpPattern::new(needle.as_bytes());
+            let expected: Vec<_> = haystack.split(needle).map(str::as_bytes).collect();
+            assert_eq!(split(&pattern, haystack.as_bytes()), expected);
+        }
+    }
+
+    #[test]
+    fn split_inclusive() {
+        const TEST_CASES: &[(&str, &str)] = &[
+            ("\r\n", "GET\r\nHost\r\n\r\n"),
+            ("\r\n", "GET\r\npartial"),
+            ("aa", "aaaaa"),
+            ("x", ""),
+            ("", "abc"),
+        ];
+
+        for (needle, haystack) in TEST_CASES {
+            let pattern = KmpPattern::new(needle.as_bytes());
+            let expected: Vec<_> = haystack
+                .split_inclusive(needle)
+                .map(str::as_bytes)
+                .collect();
+
+            assert_eq!(
+                pattern
+                    .split_inclusive(haystack.as_bytes())
+                    .collect::<Vec<_>>(),
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use core::iter::FusedIterator;
+
+use crate::{item_matches, KmpMatchable, KmpPattern, KmpSearch};
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Non-overlapping search counting every comparison of a needle item with a haystack item.
+    ///
+    /// Yields the same positions as [`KmpPattern::find`]. Successful and failed comparisons
+    /// are both counted, so the total includes the cost of falling back after a mismatch.
+    pub fn find_counted<H>(&'a self, haystack: &'a [H]) -> KmpCountedSearch<'a, N, H, false>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpCountedSearch {
+            search: self.find(haystack),
+            comparisons: 0,
+        }
+    }
+
+    /// Overlapping counterpart of [`KmpPattern::find_counted`].
+    pub fn find_overlapping_counted<H>(
+        &'a self,
+        haystack: &'a [H],
+    ) -> KmpCountedSearch<'a, N, H, true>
+    where
+        N: KmpMatchable<H>,
+    {
+        KmpCountedSearch {
+            search: self.find_overlapping(haystack),
+            comparisons: 0,
+        }
+    }
+}
+
+/// Search that counts the comparisons made so far, see [`KmpPattern::find_counted`].
+///
+/// Items are compared one at a time, without the skipping enabled by
+/// [`KmpMatchable::first_candidate`], so that every comparison is accounted for.
+pub struct KmpCountedSearch<'a, N, H, const OVERLAPPING: bool> {
+    search: KmpSearch<'a, N, H, OVERLAPPING>,
+    comparisons: usize,
+}
+
+impl<'a, N, H, const OVERLAPPING: bool> KmpCountedSearch<'a, N, H, OVERLAPPING> {
+    /// Number of needle items compared with haystack items so far.
+    pub fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+}
+
+impl<'a, N, H, const OVERLAPPING: bool> Iterator for KmpCountedSearch<'a, N, H, OVERLAPPING>
+where
+    N: KmpMatchable<H>,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let comparisons = &mut self.comparisons;
+
+        self.search.next_by(|needle, haystack| {
+            *comparisons += 1;
+            item_matches(needle, haystack)
+        })
+    }
+}
+
+impl<'a, N, H, const OVERLAPPING: bool> FusedIterator for KmpCountedSearch<'a, N, H, OVERLAPPING> where
+    N: KmpMatchable<H>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::KmpPattern;
+
+    #[test]
+    fn counted() {
+        let pattern = KmpPattern::new(b"ab");
+        let mut search = pattern.find_counted(b"aab");
+
+        assert_eq!(search.comparisons(), 0);
+        assert_eq!(search.next(), Some(1));
+        // 'a' = 'a', 'b' != 'a', fall back and 'a' = 'a', then 'b' = 'b'.
+        assert_eq!(search.comparisons(), 4);
+        assert_eq!(search.next(), None);
+        assert_eq!(search.comparisons(), 4);
+    }
+
+    #[test]
+    fn worst_case_is_linear() {
+        for (needle_len, haystack_len) in [(4, 6), (10, 100), (100, 1000)] {
+            let mut needle = vec![b'a'; needle_len - 1];
+            needle.push(b'b');
+            let haystack = vec![b'a'; haystack_len];
+
+            let pattern = KmpPattern::new(&needle);
+            let mut search = pattern.find_counted(&haystack);
+            assert_eq!(search.next(), None);
+
+            // Each item after the first `needle_len - 1` fails against `b` and then matches
+            // the `a` the table falls back to.
+            assert_eq!(
+                search.comparisons(),
+                needle_len - 1 + 2 * (haystack_len - needle_len + 1)
+            );
+        }
+    }
+
+    #[test]
+    fn overlapping_counted() {
+        let pattern = KmpPattern::new(b"aa");
+        let mut search = pattern.find_overlapping_counted(b"aaaa");
+
+        assert_eq!(search.by_ref().count(), 3);
+        assert_eq!(search.comparisons(), 4);
+    }
+}
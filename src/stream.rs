@@ -0,0 +1,490 @@
+use std::io::{self, BufRead, ErrorKind, Read};
+use std::iter::FusedIterator;
+
+use crate::{KmpIncremental, KmpMatchable, KmpPattern, KmpSearch, KmpTableItem};
+
+pub(crate) const READ_CHUNK: usize = 8 * 1024;
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Non-overlapping search over the bytes of `reader`.
+    ///
+    /// The reader is read in chunks into an internal buffer, so wrapping it in a
+    /// [`BufReader`](std::io::BufReader) is not needed. Sources that are already buffered are
+    /// better searched with [`KmpPattern::find_in_reader`], which avoids the second copy.
+    pub fn find_reader<R: Read>(&'a self, reader: R) -> KmpStreamSearch<'a, N, R, false>
+    where
+        N: KmpMatchable<u8>,
+    {
+        KmpStreamSearch::new(self.needle, &self.lsp, reader)
+    }
+
+    /// Overlapping search over the bytes of `reader`.
+    pub fn find_reader_overlapping<R: Read>(&'a self, reader: R) -> KmpStreamSearch<'a, N, R, true>
+    where
+        N: KmpMatchable<u8>,
+    {
+        KmpStreamSearch::new(self.needle, &self.lsp, reader)
+    }
+}
+
+impl<'a, N> KmpPattern<'a, N> {
+    /// Non-overlapping search over the bytes of `reader`, scanning its buffer in place.
+    pub fn find_in_reader<R: BufRead>(&'a self, reader: R) -> KmpBufReadSearch<'a, N, R, false>
+    where
+        N: KmpMatchable<u8>,
+    {
+        KmpBufReadSearch::new(self.needle, &self.lsp, reader)
+    }
+
+    /// Overlapping search over the bytes of `reader`, scanning its buffer in place.
+    pub fn find_in_reader_overlapping<R: BufRead>(
+        &'a self,
+        reader: R,
+    ) -> KmpBufReadSearch<'a, N, R, true>
+    where
+        N: KmpMatchable<u8>,
+    {
+        KmpBufReadSearch::new(self.needle, &self.lsp, reader)
+    }
+}
+
+/// Search over a [`Read`] source, yielding absolute byte offsets of matches.
+///
+/// Only the bytes of the current partial match are retained between reads, so memory use
+/// is bounded by the needle length plus an internal read chunk, and matches spanning read
+/// boundaries are found.
+pub struct KmpStreamSearch<'a, N, R, const OVERLAPPING: bool> {
+    state: KmpIncremental<'a, N, u8, OVERLAPPING>,
+    reader: R,
+    eof: bool,
+    finished: bool,
+}
+
+impl<'a, N, R, const OVERLAPPING: bool> KmpStreamSearch<'a, N, R, OVERLAPPING> {
+    pub fn new(needle: &'a [N], lsp: &'a [KmpTableItem], reader: R) -> Self {
+        Self {
+            state: KmpIncremental::new(needle, lsp),
+            reader,
+            eof: false,
+            finished: false,
+        }
+    }
+
+    /// Automaton state as `(needle_pos, haystack_pos)`, see [`KmpIncremental::state`].
+    pub fn state(&self) -> (usize, u64) {
+        self.state.state()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<'a, N, R, const OVERLAPPING: bool> KmpStreamSearch<'a, N, R, OVERLAPPING>
+where
+    R: Read,
+{
+    fn refill(&mut self) -> io::Result<()> {
+        self.state.compact();
+
+        let buffer = &mut self.state.buffer;
+        let filled = buffer.len();
+        buffer.resize(filled + READ_CHUNK, 0);
+
+        let read = loop {
+            match self.reader.read(&mut buffer[filled..]) {
+                Ok(read) => break read,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    buffer.truncate(filled);
+                    return Err(err);
+                }
+            }
+        };
+
+        buffer.truncate(filled + read);
+        if read == 0 {
+            self.eof = true;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, N, R, const OVERLAPPING: bool> Iterator for KmpStreamSearch<'a, N, R, OVERLAPPING>
+where
+    N: KmpMatchable<u8>,
+    R: Read,
+{
+    type Item = io::Result<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pos) = self.state.next_match() {
+                return Some(Ok(pos));
+            }
+
+            if self.eof {
+                if self.finished {
+                    return None;
+                }
+
+                // The empty needle also matches once at the very end of the stream.
+                self.finished = true;
+                if self.state.needle.is_empty() {
+                    return Some(Ok(self.state.stream_len()));
+                }
+
+                return None;
+            }
+
+            if let Err(err) = self.refill() {
+                return Some(Err(err));
+            }
+        }
+    }
+}
+
+impl<'a, N, R, const OVERLAPPING: bool> FusedIterator for KmpStreamSearch<'a, N, R, OVERLAPPING>
+where
+    N: KmpMatchable<u8>,
+    R: Read,
+{
+}
+
+/// Search over a [`BufRead`] source, yielding absolute byte offsets of matches.
+///
+/// The reader's buffer is searched where it is. Only a partial match left pending at the
+/// end of a buffer is copied, together with at most a needle length of the next buffer,
+/// so matches spanning refills are found.
+pub struct KmpBufReadSearch<'a, N, R, const OVERLAPPING: bool> {
+    needle: &'a [N],
+    lsp: &'a [KmpTableItem],
+    reader: R,
+    // Bytes of the pending partial match, followed by the first `joined` bytes of the
+    // reader's buffer once it has been filled again. The search runs over `tail` while it
+    // is not empty, and over the reader's buffer otherwise.
+    tail: Vec<u8>,
+    joined: usize,
+    // Stream offset of the haystack currently searched.
+    offset: u64,
+    needle_pos: usize,
+    haystack_pos: usize,
+    finished: bool,
+}
+
+impl<'a, N, R, const OVERLAPPING: bool> KmpBufReadSearch<'a, N, R, OVERLAPPING> {
+    pub fn new(needle: &'a [N], lsp: &'a [KmpTableItem], reader: R) -> Self {
+        Self {
+            needle,
+            lsp,
+            reader,
+            tail: Vec::new(),
+            joined: 0,
+            offset: 0,
+            needle_pos: 0,
+            haystack_pos: 0,
+            finished: false,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Continues the search over `haystack` from the cursor `(needle_pos, haystack_pos)`. The
+/// empty needle's match at the end of `haystack` is left out, as more input may follow.
+fn search_from<N, const OVERLAPPING: bool>(
+    needle: &[N],
+    lsp: &[KmpTableItem],
+    cursor: (&mut usize, &mut usize),
+    haystack: &[u8],
+) -> Option<usize>
+where
+    N: KmpMatchable<u8>,
+{
+    let (needle_pos, haystack_pos) = cursor;
+
+    if needle.is_empty() {
+        if *haystack_pos >= haystack.len() {
+            return None;
+        }
+
+        *haystack_pos += 1;
+        return Some(*haystack_pos - 1);
+    }
+
+    let mut search: KmpSearch<'_, N, u8, OVERLAPPING> = KmpSearch {
+        needle_pos: *needle_pos,
+        haystack_pos: *haystack_pos,
+        ..KmpSearch::new(needle, lsp, haystack)
+    };
+
+    let found = search.next();
+    *needle_pos = search.needle_pos;
+    *haystack_pos = search.haystack_pos;
+    found
+}
+
+impl<'a, N, R, const OVERLAPPING: bool> Iterator for KmpBufReadSearch<'a, N, R, OVERLAPPING>
+where
+    N: KmpMatchable<u8>,
+    R: BufRead,
+{
+    type Item = io::Result<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.finished {
+                return None;
+            }
+
+            if self.joined != 0 {
+                let cursor = (&mut self.needle_pos, &mut self.haystack_pos);
+                if let Some(pos) =
+                    search_from::<_, OVERLAPPING>(self.needle, self.lsp, cursor, &self.tail)
+                {
+                    return Some(Ok(self.offset + pos as u64));
+                }
+
+                // A partial match shorter than the joined bytes lies within the reader's
+                // buffer, so the search continues there. Otherwise the buffer was shorter
+                // than the needle and is kept along with the partial match.
+                let before = self.tail.len() - self.joined;
+                let keep_from = self.haystack_pos - self.needle_pos;
+                if keep_from >= before {
+                    self.tail.clear();
+                    self.offset += before as u64;
+                    self.haystack_pos -= before;
+                } else {
+                    self.reader.consume(self.joined);
+                    self.tail.drain(..keep_from);
+                    self.offset += keep_from as u64;
+                    self.haystack_pos -= keep_from;
+                }
+
+                self.joined = 0;
+                continue;
+            }
+
+            let buffer = match self.reader.fill_buf() {
+                Ok(buffer) => buffer,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Some(Err(err)),
+            };
+
+            if buffer.is_empty() {
+                // The empty needle also matches once at the very end of the stream.
+                self.finished = true;
+                if self.needle.is_empty() {
+                    return Some(Ok(self.offset));
+                }
+
+                return None;
+            }
+
+            if !self.tail.is_empty() {
+                self.joined = buffer.len().min(self.needle.len());
+                self.tail.extend_from_slice(&buffer[..self.joined]);
+                continue;
+            }
+
+            let cursor = (&mut self.needle_pos, &mut self.haystack_pos);
+            if let Some(pos) = search_from::<_, OVERLAPPING>(self.needle, self.lsp, cursor, buffer)
+            {
+                return Some(Ok(self.offset + pos as u64));
+            }
+
+            let len = buffer.len();
+            let keep_from = self.haystack_pos - self.needle_pos;
+            self.tail.extend_from_slice(&buffer[keep_from..]);
+            self.reader.consume(len);
+            self.offset += keep_from as u64;
+            self.haystack_pos -= keep_from;
+        }
+    }
+}
+
+impl<'a, N, R, const OVERLAPPING: bool> FusedIterator for KmpBufReadSearch<'a, N, R, OVERLAPPING>
+where
+    N: KmpMatchable<u8>,
+    R: BufRead,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, BufReader, Read};
+
+    use crate::test_util::{wildcard_needle, xorshift};
+    use crate::KmpPattern;
+
+    struct OneByte<'a>(&'a [u8]);
+
+    impl Read for OneByte<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.0.split_first() {
+                Some((&first, rest)) if !buf.is_empty() => {
+                    buf[0] = first;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    fn offsets<I: Iterator<Item = usize>>(matches: I) -> Vec<u64> {
+        matches.map(|pos| pos as u64).collect()
+    }
+
+    #[test]
+    fn state() {
+        let pattern = KmpPattern::new(b"abcd");
+        let mut search = pattern.find_reader(OneByte(b"xabcxabc"));
+
+        assert!(search.next().is_none());
+        assert_eq!(search.state(), (3, 8));
+    }
+
+    #[test]
+    fn one_byte_reads() {
+        const TEST_CASES: &[(&[u8], &[u8])] = &[
+            (b"abc", b"abcabc"),
+            (b"aa", b"aaaaa"),
+            (b"abab", b"xabababababx"),
+            (b"aab", b"aaabaabaaab"),
+            (b"xyz", b"abc"),
+            (b"", b"abc"),
+            (b"abc", b""),
+        ];
+
+        for (needle, haystack) in TEST_CASES {
+            let pattern = KmpPattern::new(needle);
+
+            let found: Vec<_> = pattern
+                .find_reader(OneByte(haystack))
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(found, offsets(pattern.find(haystack)));
+
+            let found: Vec<_> = pattern
+                .find_reader_overlapping(OneByte(haystack))
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(found, offsets(pattern.find_overlapping(haystack)));
+        }
+    }
+
+    #[test]
+    fn large_reads() {
+        let mut haystack = Vec::new();
+        for i in 0..10_000u32 {
+            haystack.extend_from_slice(if i % 7 == 0 { b"needle" } else { b"hay" });
+        }
+
+        let pattern = KmpPattern::new(b"needle");
+        let found: Vec<_> = pattern
+            .find_reader(haystack.as_slice())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(found, offsets(pattern.find(&haystack)));
+    }
+
+    #[test]
+    fn buf_reader() {
+        let haystack = b"xabcabcxxabcx";
+        let pattern = KmpPattern::new(b"abc");
+
+        for capacity in 1..=4 {
+            let reader = BufReader::with_capacity(capacity, OneByte(haystack));
+            let found: Vec<_> = pattern
+                .find_reader(reader)
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(found, offsets(pattern.find(haystack)));
+        }
+    }
+
+    #[test]
+    fn in_reader_matches_slice_search() {
+        let mut next = xorshift(0x3c6e_f372);
+
+        for needle in [
+            &b"a"[..],
+            b"aa",
+            b"aba",
+            b"a?a",
+            b"?b",
+            b"ab?ab",
+            b"b??b",
+            b"",
+        ] {
+            let needle = wildcard_needle(needle);
+            let pattern = KmpPattern::new(&needle);
+
+            for _ in 0..100 {
+                let haystack: Vec<u8> = (0..next(32)).map(|_| b"ab"[next(2)]).collect();
+
+                for capacity in 1..=7 {
+                    let reader = BufReader::with_capacity(capacity, haystack.as_slice());
+                    let found: Vec<_> = pattern
+                        .find_in_reader(reader)
+                        .collect::<Result<_, _>>()
+                        .unwrap();
+                    assert_eq!(found, offsets(pattern.find(&haystack)));
+
+                    let reader = BufReader::with_capacity(capacity, haystack.as_slice());
+                    let found: Vec<_> = pattern
+                        .find_in_reader_overlapping(reader)
+                        .collect::<Result<_, _>>()
+                        .unwrap();
+                    assert_eq!(
+                        found,
+                        offsets(pattern.find_overlapping(&haystack)),
+                        "needle: {:?}, haystack: {:?}, capacity: {}",
+                        needle,
+                        haystack,
+                        capacity
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn in_reader_spans_refills() {
+        let haystack = b"xabcabcxxabcx";
+        let pattern = KmpPattern::new(b"abc");
+
+        let found: Vec<_> = pattern
+            .find_in_reader(BufReader::with_capacity(2, &haystack[..]))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(found, [1, 4, 9]);
+
+        let mut search = pattern.find_in_reader(&haystack[..]);
+        assert_eq!(search.by_ref().count(), 3);
+        assert!(search.next().is_none());
+        assert!(search.into_inner().is_empty());
+    }
+
+    #[test]
+    fn read_error() {
+        struct Failing;
+
+        impl Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("broken"))
+            }
+        }
+
+        let pattern = KmpPattern::new(b"ab");
+        let mut search = pattern.find_reader(Failing);
+        assert!(search.next().unwrap().is_err());
+
+        let mut search = pattern.find_in_reader(BufReader::new(Failing));
+        assert!(search.next().unwrap().is_err());
+    }
+}